@@ -0,0 +1,188 @@
+use cw::{BLOCK, Crosswords, Dir, Point, Range};
+use rand::{self, Rng};
+use std::cmp::{self, Reverse};
+
+/// Arranges a given set of words so that each one crosses at least one other, like the vocabulary
+/// puzzles used in classrooms: There is no fixed grid size and there are no filler words. The
+/// canvas grows as needed and is cropped to the letters after each word.
+pub struct CrissCross {
+    words: Vec<Vec<char>>,
+}
+
+/// An arrangement of the words.
+pub struct Layout {
+    /// The grid, cropped to the smallest rectangle containing all letters.
+    pub cw: Crosswords,
+    /// The words that couldn't be connected to the others.
+    pub unplaced: Vec<Vec<char>>,
+    /// The number of cells shared by two words.
+    pub crossings: usize,
+}
+
+/// Returns all ranges in which the word would share a cell with a matching letter in the grid.
+fn candidate_ranges(cw: &Crosswords, word: &[char]) -> Vec<Range> {
+    let mut ranges = Vec::new();
+    for y in 0..(cw.get_height() as i32) {
+        for x in 0..(cw.get_width() as i32) {
+            let point = Point::new(x, y);
+            let c = match cw.get_char(point) {
+                Some(c) if c != BLOCK => c,
+                _ => continue,
+            };
+            for &dir in &[Dir::Right, Dir::Down] {
+                for (i, _) in word.iter().enumerate().filter(|&(_, &wc)| wc == c) {
+                    ranges.push(Range {
+                                    point: point - dir.point() * i as i32,
+                                    dir,
+                                    len: word.len(),
+                                });
+                }
+            }
+        }
+    }
+    ranges
+}
+
+/// Returns the number of letters the word would share with perpendicular words if it were placed
+/// in the range, or `None` if it wouldn't cross any word, would be adjacent to a parallel word, or
+/// if one of its other letters would touch a letter that doesn't belong to it.
+fn count_crossings(cw: &Crosswords, range: Range, word: &[char]) -> Option<usize> {
+    let dp = range.dir.point();
+    let odp = range.dir.other().point();
+    if !cw.is_word_allowed(range.point, range.dir, word) || cw.is_letter(range.point - dp) ||
+       cw.is_letter(range.point + dp * range.len as i32) {
+        return None;
+    }
+    let mut crossings = 0;
+    for p in range.points() {
+        if cw.is_letter(p) {
+            if !cw.both_borders(p, range.dir) {
+                return None;
+            }
+            crossings += 1;
+        } else if cw.is_letter(p - odp) || cw.is_letter(p + odp) {
+            return None;
+        }
+    }
+    if crossings == 0 { None } else { Some(crossings) }
+}
+
+impl Layout {
+    /// Creates a layout containing only the given word.
+    fn new(word: &[char]) -> Layout {
+        let mut cw = Crosswords::new(word.len(), 1);
+        cw.try_word(Point::new(0, 0), Dir::Right, word);
+        Layout {
+            cw,
+            unplaced: Vec::new(),
+            crossings: 0,
+        }
+    }
+
+    /// Returns the number of cells of the grid.
+    pub fn area(&self) -> usize {
+        self.cw.get_width() * self.cw.get_height()
+    }
+
+    /// Returns the criteria by which layouts are compared, the best one first: the fewest unplaced
+    /// words, the smallest area and the most crossings.
+    fn rank(&self) -> (usize, usize, Reverse<usize>) {
+        (self.unplaced.len(), self.area(), Reverse(self.crossings))
+    }
+
+    /// Places the word where it crosses the most letters and, among those positions, where it
+    /// keeps the grid smallest. Returns `false` if it can't be placed anywhere.
+    fn place(&mut self, word: &[char]) -> bool {
+        let (width, height) = (self.cw.get_width() as i32, self.cw.get_height() as i32);
+        let pad = word.len();
+        let mut cw = self.cw.padded(pad, pad, pad, pad);
+        let pad = pad as i32;
+        let area = |range: &Range| {
+            let end = range.point + range.dir.point() * (range.len as i32 - 1);
+            (cmp::max(end.x + 1, pad + width) - cmp::min(range.point.x, pad)) *
+            (cmp::max(end.y + 1, pad + height) - cmp::min(range.point.y, pad))
+        };
+        let best = candidate_ranges(&cw, word)
+            .into_iter()
+            .filter_map(|range| count_crossings(&cw, range, word).map(|c| (range, c)))
+            .max_by_key(|&(ref range, crossings)| (crossings, Reverse(area(range))));
+        match best {
+            Some((range, crossings)) => {
+                cw.try_word(range.point, range.dir, word);
+                self.cw = cw.cropped();
+                self.crossings += crossings;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl CrissCross {
+    /// Creates a `CrissCross` for the given words. Duplicates and words with fewer than two
+    /// letters are ignored.
+    pub fn new<T: IntoIterator<Item = Vec<char>>>(words: T) -> CrissCross {
+        let mut words: Vec<Vec<char>> = words.into_iter().filter(|word| word.len() > 1).collect();
+        words.sort_by(|w0, w1| w1.len().cmp(&w0.len()).then_with(|| w0.cmp(w1)));
+        words.dedup();
+        CrissCross { words }
+    }
+
+    /// Starts with the first word and adds the others in the given order, each one in its best
+    /// position. Words that can't be placed yet are retried after the others, until no more words
+    /// fit.
+    fn arrange(words: &[Vec<char>]) -> Option<Layout> {
+        let mut layout = Layout::new(words.first()?);
+        let mut pending = words[1..].to_vec();
+        loop {
+            let count = pending.len();
+            pending.retain(|word| !layout.place(word));
+            if pending.is_empty() || pending.len() == count {
+                break;
+            }
+        }
+        layout.unplaced = pending;
+        Some(layout)
+    }
+
+    /// Arranges the words in `samples` different orders, the first one longest first and the
+    /// others random, and returns the best layout: the one with the fewest unplaced words, then the
+    /// smallest area, then the most crossings. Returns `None` if there are no words.
+    pub fn layout(&self, samples: usize) -> Option<Layout> {
+        let mut rng = rand::thread_rng();
+        let mut order = self.words.clone();
+        let mut best: Option<Layout> = None;
+        for i in 0..cmp::max(samples, 1) {
+            if i > 0 {
+                rng.shuffle(&mut order);
+            }
+            let layout = CrissCross::arrange(&order)?;
+            match best {
+                Some(ref best_layout) if best_layout.rank() <= layout.rank() => (),
+                _ => best = Some(layout),
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::*;
+
+    #[test]
+    fn test_layout() {
+        let words = strs_to_cvecs(&["HELLO", "WORLD", "LOW", "OWL", "DOLL", "HOLD", "ROW"]);
+        let layout = CrissCross::new(words.clone()).layout(20).unwrap();
+        assert!(layout.unplaced.is_empty());
+        assert!(layout.crossings >= words.len() - 1);
+        assert_eq!(words.len(), layout.cw.get_words().len());
+        let dicts = [words_to_dict(words)];
+        assert!(layout.cw.validate(&dicts, 1, 0).is_empty());
+        let layout = CrissCross::new(strs_to_cvecs(&["ABC", "XYZ"])).layout(1).unwrap();
+        assert_eq!(vec![str_to_cvec("XYZ")], layout.unplaced);
+        assert_eq!(3, layout.area());
+        assert!(CrissCross::new(Vec::new()).layout(1).is_none());
+    }
+}
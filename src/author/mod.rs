@@ -1,15 +1,26 @@
+mod criss_cross;
+mod slots;
+mod solutions;
 mod word_range_iter;
 
+pub use author::criss_cross::CrissCross;
+pub use author::slots::SlotConstraints;
+pub use author::solutions::Solutions;
+
 use cw::{BLOCK, Crosswords, Dir, Point, Range};
-use dict::Dict;
+use dict::{DEFAULT_MAX_GAP, DEFAULT_MAX_N, Dict, Stemmer, VariantGroups};
 use itertools::Itertools;
 use word_stats::WordStats;
-use std::cmp;
 use std::cmp::Ordering;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 use std::usize;
+use author::slots::SlotWords;
 use author::word_range_iter::WordRangeIter;
 
+/// The number of candidates `complete_cw` tries between two checks of the deadline.
+const DEADLINE_CHECK_INTERVAL: usize = 256;
+
 /// A `RangeSet` represents a choice of ranges in the crosswords grid one of which must be filled
 /// in order to satisfy the requirements.
 #[derive(Clone, PartialEq)]
@@ -59,6 +70,14 @@ struct StackItem<'a> {
     iter: WordRangeIter<'a>,
     range: Range,
     attempts: usize,
+    /// The words that were replaced by the word in the range, to restore when it is removed.
+    replaced: Vec<(Range, PlacedWord)>,
+}
+
+/// A word in the grid, together with its stem if the `Author` has a stemmer.
+struct PlacedWord {
+    word: Vec<char>,
+    stem: Option<Vec<char>>,
 }
 
 /// An `Author` produces crossword grids from a given set of dictionaries.
@@ -68,9 +87,24 @@ pub struct Author<'a> {
     min_crossing: usize,
     min_crossing_percent: usize,
     max_attempts: usize,
-    stats: WordStats,
+    stats: WordStats<'a>,
     verbose: bool,
     stack: Vec<StackItem<'a>>,
+    /// The current candidate, decoded from the dictionary's compact representation. The grid
+    /// stores words as `char`s, so each candidate is decoded into this buffer, which is reused to
+    /// avoid allocating.
+    word_buffer: Vec<char>,
+    /// The words allowed in the constrained slots, if there are any.
+    slot_words: Option<SlotWords>,
+    stemmer: Option<&'a Stemmer>,
+    variants: Option<&'a VariantGroups>,
+    /// The words in the grid by their ranges, to check candidates for related words.
+    placed_words: HashMap<Range, PlacedWord>,
+    /// The number of words in the grid with each stem.
+    placed_stems: HashMap<Vec<char>, usize>,
+    deadline: Option<Instant>,
+    /// The number of candidates to try before the deadline is checked again.
+    until_deadline_check: usize,
 }
 
 /// Replaces the `$result` with the given range set `$rs` if that has a lower estimated word count.
@@ -90,8 +124,14 @@ macro_rules! result_range_set {
 impl<'a> Author<'a> {
     /// Creates a new `Author` with the given initial crosswords grid and the given dictionaries.
     pub fn new(init_cw: &Crosswords, dicts: &'a [Dict]) -> Author<'a> {
-        let mut stats = WordStats::new(3);
-        stats.add_words(dicts.iter().flat_map(|dict| dict.all_words()));
+        // Use the same n-gram depth and pair gaps as the dictionaries, so that their index can be
+        // reused.
+        let max_n = dicts.iter().map(Dict::get_max_n).min().unwrap_or(DEFAULT_MAX_N);
+        let max_gap = dicts.iter().map(Dict::get_max_gap).min().unwrap_or(DEFAULT_MAX_GAP);
+        let mut stats = WordStats::new(max_n, max_gap);
+        for dict in dicts {
+            stats.add_dict(dict);
+        }
         Author {
             dicts: dicts,
             stats: stats,
@@ -101,6 +141,22 @@ impl<'a> Author<'a> {
             min_crossing_percent: 0,
             max_attempts: usize::MAX,
             stack: Vec::new(),
+            word_buffer: Vec::new(),
+            slot_words: None,
+            stemmer: None,
+            variants: None,
+            placed_words: init_cw.word_ranges()
+                .map(|range| {
+                         let word = PlacedWord {
+                             word: init_cw.chars(range).collect(),
+                             stem: None,
+                         };
+                         (range, word)
+                     })
+                .collect(),
+            placed_stems: HashMap::new(),
+            deadline: None,
+            until_deadline_check: 0,
         }
     }
 
@@ -127,6 +183,45 @@ impl<'a> Author<'a> {
         self
     }
 
+    /// Sets constraints that the words in certain slots have to satisfy, e. g. to place theme
+    /// words, and return the modified `Author`.
+    pub fn with_slot_constraints(mut self, slots: &SlotConstraints) -> Author<'a> {
+        self.slot_words = Some(slots.allowed_words(self.dicts));
+        self
+    }
+
+    /// Forbids words that are related to a word already in the grid according to the stemmer, e. g.
+    /// `RUN` and `RUNNING`, and return the modified `Author`.
+    pub fn with_stemmer(mut self, stemmer: &'a Stemmer) -> Author<'a> {
+        self.stemmer = Some(stemmer);
+        for placed in self.placed_words.values_mut() {
+            let stem = stemmer.stem(&placed.word);
+            *self.placed_stems.entry(stem.clone()).or_insert(0) += 1;
+            placed.stem = Some(stem);
+        }
+        self
+    }
+
+    /// Allows at most one word of each group of variants in the grid, and return the modified
+    /// `Author`.
+    pub fn with_variants(mut self, variants: &'a VariantGroups) -> Author<'a> {
+        self.variants = Some(variants);
+        self
+    }
+
+    /// Makes `complete_cw` give up and return `None` once the deadline has passed, and return the
+    /// modified `Author`.
+    pub fn with_deadline(mut self, deadline: Instant) -> Author<'a> {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Removes the deadline, if there is one, and return the modified `Author`.
+    pub fn without_deadline(mut self) -> Author<'a> {
+        self.deadline = None;
+        self
+    }
+
     /// Sets the verbosity mode and return the modified `Author`. If `verbose` is true, the current
     /// status of the crosswords grid is printed every time the algorithm backtracks.
     pub fn with_verbosity(mut self, verbose: bool) -> Author<'a> {
@@ -139,6 +234,84 @@ impl<'a> Author<'a> {
         self.dicts.iter().position(|dict| dict.contains(word))
     }
 
+    /// Returns the score of the given word in the first dictionary containing it, or None if not
+    /// found.
+    pub fn get_word_score(&self, word: &[char]) -> Option<i32> {
+        self.dicts.iter().filter_map(|dict| dict.get_score(word)).next()
+    }
+
+    /// Returns whether the word, with the given stem, is related to or a variant of one of the
+    /// words in the grid, except the ones it would replace if it were placed in the range.
+    fn has_related_word(&self, range: Range, word: &[char], stem: Option<&[char]>) -> bool {
+        if self.stemmer.is_none() && self.variants.is_none() {
+            return false;
+        }
+        let mut placed_words = self.placed_words
+            .iter()
+            .filter(|&(placed_range, _)| !Author::is_replaced_by(placed_range, &range))
+            .map(|(_, placed)| placed);
+        if let Some(stem) = stem {
+            // Only look for the word with that stem if it could be one that isn't replaced.
+            if self.placed_stems.contains_key(stem) &&
+               placed_words.clone().any(|placed| placed.stem.as_deref() == Some(stem)) {
+                return true;
+            }
+        }
+        placed_words.any(|placed| {
+                             (stem.is_some() && Stemmer::is_contained(word, &placed.word)) ||
+                             match self.variants {
+                                 Some(variants) => variants.are_variants(&placed.word, word),
+                                 None => false,
+                             }
+                         })
+    }
+
+    /// Returns whether a word in the first range is replaced when a word is put in the second one,
+    /// because it starts inside it in the same direction.
+    fn is_replaced_by(placed_range: &Range, range: &Range) -> bool {
+        placed_range.dir == range.dir && range.contains(placed_range.point)
+    }
+
+    /// Records that the word was put in the range, replacing the words that started inside it,
+    /// which are returned.
+    fn add_placed_word(&mut self, range: Range, word: PlacedWord) -> Vec<(Range, PlacedWord)> {
+        let replaced_ranges: Vec<Range> = self.placed_words
+            .keys()
+            .filter(|placed_range| Author::is_replaced_by(placed_range, &range))
+            .cloned()
+            .collect();
+        let replaced = replaced_ranges
+            .into_iter()
+            .filter_map(|r| self.remove_placed_word(&r).map(|placed| (r, placed)))
+            .collect();
+        self.insert_placed_word(range, word);
+        replaced
+    }
+
+    fn insert_placed_word(&mut self, range: Range, word: PlacedWord) {
+        if let Some(ref stem) = word.stem {
+            *self.placed_stems.entry(stem.clone()).or_insert(0) += 1;
+        }
+        self.placed_words.insert(range, word);
+    }
+
+    fn remove_placed_word(&mut self, range: &Range) -> Option<PlacedWord> {
+        let opt_word = self.placed_words.remove(range);
+        if let Some(stem) = opt_word.as_ref().and_then(|word| word.stem.as_ref()) {
+            let is_last = match self.placed_stems.get_mut(stem) {
+                Some(count) => {
+                    *count -= 1;
+                    *count == 0
+                }
+                None => false,
+            };
+            if is_last {
+                self.placed_stems.remove(stem);
+            }
+        }
+        opt_word
+    }
+
     fn is_min_crossing_possible_without(&self, range: Range, filled_range: Range) -> bool {
         if self.min_crossing_percent == 100 {
             return range.len == 0 || range.len >= self.stats.get_min_len();
@@ -208,11 +381,7 @@ impl<'a> Author<'a> {
     /// Returns the maximum number of characters of a word of the given length that don't need to
     /// be connected to a crossing word.
     fn get_max_noncrossing(&self, len: usize) -> usize {
-        if self.min_crossing > len {
-            return len;
-        }
-        let rel_min_crossing = (self.min_crossing_percent * len / 100) as usize;
-        len - cmp::max(rel_min_crossing, self.min_crossing)
+        len - Crosswords::required_crossings(len, self.min_crossing, self.min_crossing_percent)
     }
 
     /// Returns a factor for the word count estimate of a range, depending on how many neighboring
@@ -381,8 +550,8 @@ impl<'a> Author<'a> {
     }
 
     fn pop(&mut self) -> Option<StackItem<'a>> {
-        let opt_item = self.stack.pop();
-        if let Some(ref item) = opt_item {
+        let mut opt_item = self.stack.pop();
+        if let Some(ref mut item) = opt_item {
             let range = item.range;
             if self.verbose {
                 println!("{}", &self.cw);
@@ -393,6 +562,10 @@ impl<'a> Author<'a> {
                          range.dir);
             }
             self.cw.pop_word(range.point, range.dir);
+            self.remove_placed_word(&range);
+            for (replaced_range, replaced) in item.replaced.drain(..) {
+                self.insert_placed_word(replaced_range, replaced);
+            }
         }
         opt_item
     }
@@ -404,6 +577,12 @@ impl<'a> Author<'a> {
         }
     }
 
+    /// Returns whether every constrained slot of the grid holds a word that satisfies its
+    /// constraint.
+    fn are_slots_satisfied(&self) -> bool {
+        self.slot_words.iter().all(|slot_words| slot_words.are_satisfied_by(&self.cw))
+    }
+
     fn range_meets(range: &Range, bt_ranges: &HashSet<Range>) -> bool {
         bt_ranges.is_empty() ||
         bt_ranges
@@ -411,6 +590,12 @@ impl<'a> Author<'a> {
             .any(|r| range.intersects(r) || range.is_adjacent_to(r))
     }
 
+    /// Returns an iterator over the distinct grids this author can complete, which resumes the
+    /// search after each one.
+    pub fn solutions(self) -> Solutions<'a> {
+        Solutions::new(self)
+    }
+
     // TODO: Remove when https://github.com/Manishearth/rust-clippy/issues/1586 is resolved.
     #[cfg_attr(feature="cargo-clippy", allow(never_loop))]
     pub fn complete_cw(&mut self) -> Option<Crosswords> {
@@ -420,29 +605,66 @@ impl<'a> Author<'a> {
             Some(item) => item.iter, // Drop bt_ranges, as iter was successful!.
             None => {
                 match self.get_range_set() {
-                    Some(rs) => WordRangeIter::new(self.get_sorted_ranges(rs.ranges), self.dicts),
+                    Some(rs) => {
+                        WordRangeIter::new(self.get_sorted_ranges(rs.ranges),
+                                           self.dicts,
+                                           self.slot_words.as_ref())
+                    }
                     None => return None,
                 }
             }
         };
         'main: loop {
             while let Some((range, word)) = iter.next() {
-                if self.cw.try_word(range.point, range.dir, &word) {
+                if self.until_deadline_check == 0 {
+                    if let Some(deadline) = self.deadline {
+                        if Instant::now() >= deadline {
+                            return None;
+                        }
+                    }
+                    self.until_deadline_check = DEADLINE_CHECK_INTERVAL;
+                }
+                self.until_deadline_check -= 1;
+                // Decode the word into a reused buffer instead of allocating a new one.
+                self.word_buffer.clear();
+                self.word_buffer.extend(word);
+                if !self.cw.is_word_allowed(range.point, range.dir, &self.word_buffer) {
+                    continue;
+                }
+                // Only the words that fit are stemmed.
+                let stem = self.stemmer.map(|stemmer| stemmer.stem(&self.word_buffer));
+                let opt_stem = stem.as_ref().map(|s| &s[..]);
+                if !self.has_related_word(range, &self.word_buffer, opt_stem) &&
+                   self.cw.try_word(range.point, range.dir, &self.word_buffer) {
+                    let placed = PlacedWord {
+                        word: self.word_buffer.clone(),
+                        stem: stem,
+                    };
+                    let replaced = self.add_placed_word(range, placed);
                     self.stack
                         .push(StackItem {
                                   bt_ranges: bt_ranges,
                                   range: range,
                                   iter: iter,
                                   attempts: attempts + 1,
+                                  replaced: replaced,
                               });
                     match self.get_range_set() {
                         Some(rs) => {
                             bt_ranges = rs.backtrack_ranges;
                             iter = WordRangeIter::new(self.get_sorted_ranges(rs.ranges),
-                                                      self.dicts);
+                                                      self.dicts,
+                                                      self.slot_words.as_ref());
+                            attempts = 0;
+                        }
+                        None if self.are_slots_satisfied() => return Some(self.cw.clone()),
+                        None => {
+                            // The grid is complete, but a constrained slot doesn't hold a matching
+                            // word, e. g. because it was left empty. Backtrack.
+                            bt_ranges = HashSet::new();
+                            iter = WordRangeIter::new(Vec::new(), self.dicts, None);
                             attempts = 0;
                         }
-                        None => return Some(self.cw.clone()),
                     };
                 }
             }
@@ -468,27 +690,119 @@ impl<'a> Author<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use author::slots::SlotConstraint;
     use cw::Crosswords;
-    use dict::Dict;
+    use dict::Lang;
     #[cfg(feature = "nightly")]
     use test::Bencher;
     use test_util::*;
 
     #[test]
     fn test_complete_cw_possible() {
-        let dicts = vec![Dict::new(strs_to_cvecs(&["ABC", "EFG"])),
-                         Dict::new(strs_to_cvecs(&["AEX", "BFX", "CGX"]))];
+        let dicts = vec![words_to_dict(strs_to_cvecs(&["ABC", "EFG"])),
+                         words_to_dict(strs_to_cvecs(&["AEX", "BFX", "CGX"]))];
         let mut author = Author::new(&Crosswords::new(3, 3), &dicts);
         assert!(author.complete_cw().is_some());
     }
 
+    #[test]
+    fn test_complete_cw_related() {
+        let dicts = vec![words_to_dict(strs_to_cvecs(&["ABC", "EFG"])),
+                         words_to_dict(strs_to_cvecs(&["AEX", "BFX", "CGX"]))];
+        let mut stemmer = Stemmer::new(Lang::English);
+        stemmer.add_lemmas("abc efg");
+        let mut author = Author::new(&Crosswords::new(3, 3), &dicts).with_stemmer(&stemmer);
+        assert!(author.complete_cw().is_none());
+    }
+
+    /// Puts the word in the range's entry of the stack, as `complete_cw` does.
+    fn push_word(author: &mut Author, range: Range, word: &str) {
+        let word = str_to_cvec(word);
+        let stem = author.stemmer.map(|stemmer| stemmer.stem(&word));
+        let replaced = author.add_placed_word(range,
+                                              PlacedWord {
+                                                  word: word,
+                                                  stem: stem,
+                                              });
+        let iter = WordRangeIter::new(Vec::new(), author.dicts, None);
+        author.stack.push(StackItem {
+                              bt_ranges: HashSet::new(),
+                              iter: iter,
+                              range: range,
+                              attempts: 1,
+                              replaced: replaced,
+                          });
+    }
+
+    #[test]
+    fn test_pop_restores_replaced_words() {
+        let dicts = vec![words_to_dict(strs_to_cvecs(&["RUN", "RUNNING", "RUNS"]))];
+        let stemmer = Stemmer::new(Lang::English);
+        let mut author = Author::new(&Crosswords::new(7, 3), &dicts).with_stemmer(&stemmer);
+        let range = |y, len| {
+            Range {
+                point: Point::new(0, y),
+                dir: Dir::Right,
+                len: len,
+            }
+        };
+        let runs = str_to_cvec("RUNS");
+        let runs_stem = stemmer.stem(&runs);
+        // RUNNING extends RUN and replaces it. When it is removed again, RUN is back in the grid.
+        push_word(&mut author, range(0, 3), "RUN");
+        push_word(&mut author, range(0, 7), "RUNNING");
+        assert_eq!(1, author.placed_words.len());
+        assert!(author.has_related_word(range(2, 4), &runs, Some(&runs_stem)));
+        author.pop();
+        assert_eq!(str_to_cvec("RUN"), author.placed_words[&range(0, 3)].word);
+        assert!(author.has_related_word(range(2, 4), &runs, Some(&runs_stem)));
+        author.pop();
+        assert!(author.placed_words.is_empty() && author.placed_stems.is_empty());
+        assert!(!author.has_related_word(range(2, 4), &runs, Some(&runs_stem)));
+    }
+
+    #[test]
+    fn test_complete_cw_variants() {
+        let dicts = vec![words_to_dict(strs_to_cvecs(&["ABC", "EFG"])),
+                         words_to_dict(strs_to_cvecs(&["AEX", "BFX", "CGX"]))];
+        let mut variants = VariantGroups::new(Lang::English);
+        variants.add_group("aex cgx");
+        let mut author = Author::new(&Crosswords::new(3, 3), &dicts).with_variants(&variants);
+        assert!(author.complete_cw().is_none());
+    }
+
     #[test]
     fn test_complete_cw_impossible() {
-        let dicts = vec![Dict::new(strs_to_cvecs(&["ABC", "ABCD"]))];
+        let dicts = vec![words_to_dict(strs_to_cvecs(&["ABC", "ABCD"]))];
         let mut author = Author::new(&Crosswords::new(3, 3), &dicts);
         assert!(author.complete_cw().is_none());
     }
 
+    #[test]
+    fn test_complete_cw_slots() {
+        let dicts = vec![words_to_dict(strs_to_cvecs(&["ABC", "BC"]))];
+        let mut author = Author::new(&Crosswords::new(3, 1), &dicts).with_min_crossing(0, 0);
+        let cw = author.complete_cw().unwrap();
+        assert_eq!(str_to_cvec("ABC"), cw.word_at(Point::new(0, 0), Dir::Right));
+        // ABC would cover the slot, but it starts in the cell before it.
+        let mut slots = SlotConstraints::new();
+        slots.insert(Point::new(1, 0),
+                     Dir::Right,
+                     SlotConstraint::Words(strs_to_cvecs(&["ABC", "BC"]).into_iter().collect()));
+        let mut author = Author::new(&Crosswords::new(3, 1), &dicts)
+            .with_min_crossing(0, 0)
+            .with_slot_constraints(&slots);
+        assert!(author.complete_cw().is_none());
+    }
+
+    #[test]
+    fn test_complete_cw_deadline() {
+        let dicts = vec![words_to_dict(strs_to_cvecs(&["ABC", "EFG"])),
+                         words_to_dict(strs_to_cvecs(&["AEX", "BFX", "CGX"]))];
+        let mut author = Author::new(&Crosswords::new(3, 3), &dicts).with_deadline(Instant::now());
+        assert!(author.complete_cw().is_none());
+    }
+
     #[cfg(feature = "nightly")]
     #[bench]
     fn bench_complete_cw(bencher: &mut Bencher) {
@@ -503,7 +817,7 @@ mod tests {
             .windows(height)
             .map(String::from_utf8_lossy)
             .map(str_to_cvec);
-        let dicts = vec![Dict::new(horiz_words), Dict::new(vert_words)];
+        let dicts = vec![words_to_dict(horiz_words), words_to_dict(vert_words)];
         bencher.iter(|| {
                          assert!(Author::new(&Crosswords::new(width, height), &dicts)
                                      .complete_cw()
@@ -0,0 +1,204 @@
+use cw::{Crosswords, Dir, Point, Range};
+use dict::{Dict, Lang};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::rc::Rc;
+
+/// A requirement for the word in a slot of the grid, in addition to the letters already there.
+#[derive(Clone)]
+pub enum SlotConstraint {
+    /// The word must match the regular expression as a whole.
+    Regex(Regex),
+    /// The word must be one of the given words.
+    Words(HashSet<Vec<char>>),
+}
+
+impl SlotConstraint {
+    /// Returns whether the word satisfies the constraint.
+    pub fn is_satisfied_by(&self, word: &[char]) -> bool {
+        match *self {
+            SlotConstraint::Regex(ref regex) => regex.is_match(&word.iter().collect::<String>()),
+            SlotConstraint::Words(ref words) => words.contains(word),
+        }
+    }
+
+    /// Returns the sorted indices of the words in the dictionary that satisfy the constraint.
+    pub fn allowed_words(&self, dict: &Dict) -> Vec<u32> {
+        let mut chars = Vec::new();
+        let mut string = String::new();
+        dict.all_words()
+            .enumerate()
+            .filter(|&(_, word)| match *self {
+                        SlotConstraint::Regex(ref regex) => {
+                            string.clear();
+                            string.extend(word.chars());
+                            regex.is_match(&string)
+                        }
+                        SlotConstraint::Words(ref words) => {
+                            chars.clear();
+                            chars.extend(word);
+                            words.contains(&chars)
+                        }
+                    })
+            .map(|(i, _)| i as u32)
+            .collect()
+    }
+}
+
+/// The sorted indices of the words allowed in a slot, for each dictionary.
+pub type AllowedWords = Rc<Vec<Vec<u32>>>;
+
+/// The words allowed in each constrained slot, worked out once for a given list of dictionaries,
+/// so that the search only needs to intersect them with the words matching a pattern.
+pub struct SlotWords {
+    slots: HashMap<(Point, Dir), (SlotConstraint, AllowedWords)>,
+    /// An empty list for each dictionary, for the ranges that no word is allowed in.
+    no_words: AllowedWords,
+}
+
+impl SlotWords {
+    /// Returns the words allowed in the given range, if it covers the first cell of a constrained
+    /// slot in the same direction. A word in a range that starts at a different cell would
+    /// replace the slot's word, so no words are allowed there.
+    pub fn get(&self, range: &Range) -> Option<&AllowedWords> {
+        let mut result = None;
+        for (&(point, dir), (_, words)) in &self.slots {
+            if dir == range.dir && range.contains(point) {
+                if point != range.point {
+                    return Some(&self.no_words);
+                }
+                result = Some(words);
+            }
+        }
+        result
+    }
+
+    /// Returns whether a word starts at each constrained slot of the grid and satisfies the
+    /// slot's constraint.
+    pub fn are_satisfied_by(&self, cw: &Crosswords) -> bool {
+        self.slots
+            .iter()
+            .all(|(&(point, dir), (constraint, _))| {
+                     cw.has_hint_at_dir(point, dir) &&
+                     constraint.is_satisfied_by(&cw.word_at(point, dir))
+                 })
+    }
+}
+
+/// The constraints for the words starting at given points in given directions, e. g. theme slots.
+pub struct SlotConstraints {
+    constraints: HashMap<(Point, Dir), SlotConstraint>,
+}
+
+impl SlotConstraints {
+    /// Creates an empty set of constraints.
+    pub fn new() -> SlotConstraints {
+        SlotConstraints { constraints: HashMap::new() }
+    }
+
+    /// Requires the word starting at `point` in direction `dir` to satisfy the constraint.
+    pub fn insert(&mut self, point: Point, dir: Dir, constraint: SlotConstraint) {
+        self.constraints.insert((point, dir), constraint);
+    }
+
+    /// Returns the words from each of the dictionaries that are allowed in the constrained slots.
+    pub fn allowed_words(&self, dicts: &[Dict]) -> SlotWords {
+        let slots = self.constraints
+            .iter()
+            .map(|(&slot, constraint)| {
+                     let lists = dicts.iter().map(|dict| constraint.allowed_words(dict)).collect();
+                     (slot, (constraint.clone(), Rc::new(lists)))
+                 })
+            .collect();
+        SlotWords {
+            slots,
+            no_words: Rc::new(vec![Vec::new(); dicts.len()]),
+        }
+    }
+
+    /// Parses constraints, one per line, in the format `X Y DIR CONSTRAINT`, where `DIR` is
+    /// `right` or `down` and the constraint is either a regular expression, e. g. `.*ING`, or an
+    /// `@` followed by the name of a word list, relative to `base_dir`. Regular expressions are
+    /// matched against the whole word, in capital letters, and word lists are normalized according
+    /// to the language.
+    pub fn parse(text: &str, lang: Lang, base_dir: &Path) -> Result<SlotConstraints, String> {
+        let mut slots = SlotConstraints::new();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let parts: Vec<&str> = line.splitn(4, ' ').collect();
+            if parts.len() != 4 {
+                return Err(format!("invalid slot constraint: {}", line));
+            }
+            let coord = |s: &str| s.parse().map_err(|_| format!("invalid coordinate: {}", s));
+            let point = Point::new(coord(parts[0])?, coord(parts[1])?);
+            let dir = match parts[2] {
+                "right" => Dir::Right,
+                "down" => Dir::Down,
+                _ => return Err(format!("invalid direction: {}", parts[2])),
+            };
+            let constraint = if parts[3].starts_with('@') {
+                let path = base_dir.join(parts[3][1..].trim());
+                let file = File::open(&path)
+                    .map_err(|err| format!("{}: {}", path.display(), err))?;
+                let words = BufReader::new(file)
+                    .lines()
+                    .map_while(Result::ok)
+                    .filter_map(|line| Dict::parse_line(line, lang))
+                    .map(|(word, _, _)| word)
+                    .collect();
+                SlotConstraint::Words(words)
+            } else {
+                let regex = Regex::new(&format!("^(?:{})$", parts[3].trim()))
+                    .map_err(|err| err.to_string())?;
+                SlotConstraint::Regex(regex)
+            };
+            slots.insert(point, dir, constraint);
+        }
+        Ok(slots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use test_util::*;
+
+    #[test]
+    fn test_parse() {
+        let text = "0 0 right .*ING\n\n2 1 down [A-C]{3}\n";
+        let slots = SlotConstraints::parse(text, Lang::English, &env::temp_dir()).unwrap();
+        let dict = scored_dict(vec![(str_to_cvec("SING"), 30),
+                                    (str_to_cvec("SONG"), 20),
+                                    (str_to_cvec("CAB"), 10)]);
+        let range = |x, y, dir, len| {
+            Range {
+                point: Point::new(x, y),
+                dir,
+                len,
+            }
+        };
+        let slot_words = slots.allowed_words(&[dict]);
+        let allowed = |range: Range| slot_words.get(&range).unwrap()[0].clone();
+        assert_eq!(vec![0], allowed(range(0, 0, Dir::Right, 4)));
+        assert_eq!(vec![2], allowed(range(2, 1, Dir::Down, 3)));
+        assert!(slot_words.get(&range(0, 0, Dir::Down, 4)).is_none());
+        assert!(slot_words.get(&range(1, 0, Dir::Right, 3)).is_none());
+        // A range that starts before a slot would replace the slot's word.
+        assert!(allowed(range(2, 0, Dir::Down, 4)).is_empty());
+        let mut cw = Crosswords::new(4, 4);
+        assert!(cw.try_word(Point::new(0, 0), Dir::Right, &str_to_cvec("SING")));
+        assert!(cw.try_word(Point::new(2, 1), Dir::Down, &str_to_cvec("CAB")));
+        assert!(slot_words.are_satisfied_by(&cw));
+        cw.pop_word(Point::new(2, 1), Dir::Down);
+        assert!(cw.try_word(Point::new(2, 0), Dir::Down, &str_to_cvec("NCAB")));
+        assert!(!slot_words.are_satisfied_by(&cw));
+        assert!(SlotConstraints::parse("0 0 up .*", Lang::English, &env::temp_dir()).is_err());
+        assert!(SlotConstraints::parse("0 0 right @nonexistent.txt",
+                                       Lang::English,
+                                       &env::temp_dir())
+                        .is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use author::Author;
+use cw::Crosswords;
+use std::collections::HashSet;
+
+/// An iterator over the grids an `Author` completes, created by `Author::solutions`. After each
+/// grid it resumes the backtracking search where it left off, so the grids are found in the same
+/// order as by repeated calls to `complete_cw`.
+pub struct Solutions<'a> {
+    author: Author<'a>,
+    /// The canonical forms of the grids returned so far, if duplicates are skipped.
+    seen: Option<HashSet<Crosswords>>,
+    /// The number of words to keep in the grid when searching for the next solution.
+    backtrack_to: Option<usize>,
+    /// The maximum number of grids to complete, including skipped duplicates.
+    limit: Option<usize>,
+    count: usize,
+    exhausted: bool,
+}
+
+impl<'a> Solutions<'a> {
+    /// Creates an iterator over the solutions of the author.
+    pub fn new(author: Author<'a>) -> Solutions<'a> {
+        Solutions {
+            author,
+            seen: None,
+            backtrack_to: None,
+            limit: None,
+            count: 0,
+            exhausted: false,
+        }
+    }
+
+    /// Makes the iterator skip grids that are equal to an earlier one or to its transposition,
+    /// and return the modified iterator.
+    pub fn distinct(mut self) -> Solutions<'a> {
+        self.seen = Some(HashSet::new());
+        self
+    }
+
+    /// Makes the iterator remove all but the first `n` words after each solution, and return the
+    /// modified iterator. This leads to more varied grids, but skips the ones that only differ
+    /// in the removed words.
+    pub fn backtrack_to(mut self, n: usize) -> Solutions<'a> {
+        self.backtrack_to = Some(n);
+        self
+    }
+
+    /// Makes the iterator stop after completing `limit` grids, including duplicates, and return
+    /// the modified iterator.
+    pub fn limit(mut self, limit: usize) -> Solutions<'a> {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Counts the grid that the author has just completed, e. g. in a call to `complete_cw`, as
+    /// the first solution, and return the modified iterator, which resumes the search after it.
+    pub fn after(mut self, cw: &Crosswords) -> Solutions<'a> {
+        self.add_solution(cw);
+        self
+    }
+
+    /// Counts the grid and backtracks, and returns whether it is not a duplicate.
+    fn add_solution(&mut self, cw: &Crosswords) -> bool {
+        self.count += 1;
+        if let Some(n) = self.backtrack_to {
+            self.author.pop_to_n_words(n);
+        }
+        match self.seen {
+            Some(ref mut seen) => seen.insert(cw.canonical()),
+            None => true,
+        }
+    }
+
+    /// Returns the author, e. g. to look up the categories and scores of the words.
+    pub fn author(&self) -> &Author<'a> {
+        &self.author
+    }
+}
+
+impl<'a> Iterator for Solutions<'a> {
+    type Item = Crosswords;
+
+    fn next(&mut self) -> Option<Crosswords> {
+        while !self.exhausted {
+            match self.limit {
+                Some(limit) if self.count >= limit => return None,
+                _ => (),
+            }
+            let cw = match self.author.complete_cw() {
+                Some(cw) => cw,
+                None => {
+                    // Otherwise the next call would start the search from scratch.
+                    self.exhausted = true;
+                    return None;
+                }
+            };
+            if self.add_solution(&cw) {
+                return Some(cw);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use author::Author;
+    use cw::Crosswords;
+    use test_util::*;
+
+    #[test]
+    fn test_solutions() {
+        let dicts = vec![words_to_dict(strs_to_cvecs(&["AB", "CD", "AC", "BD"]))];
+        let author = || Author::new(&Crosswords::new(2, 2), &dicts);
+        // The search finds the grid and its transposition, each by placing the words in different
+        // orders, but they all have the same canonical form.
+        assert_eq!(4, author().solutions().count());
+        assert_eq!(1, author().solutions().limit(1).count());
+        let dicts = vec![words_to_dict(strs_to_cvecs(&["AB", "CD", "AC", "BD", "BE", "CE"]))];
+        let solutions: Vec<_> = Author::new(&Crosswords::new(2, 2), &dicts).solutions().collect();
+        assert!(solutions.len() > 1);
+        assert!(solutions.iter().all(|cw| cw.is_full()));
+    }
+
+    #[test]
+    fn test_distinct() {
+        let dicts = vec![words_to_dict(strs_to_cvecs(&["AB", "CD", "AC", "BD"]))];
+        let author = || Author::new(&Crosswords::new(2, 2), &dicts);
+        let solutions: Vec<_> = author().solutions().distinct().collect();
+        assert_eq!(1, solutions.len());
+        assert!(author().solutions().all(|cw| cw.canonical() == solutions[0].canonical()));
+        // The limit counts the skipped duplicates, too.
+        assert_eq!(1, author().solutions().distinct().limit(4).count());
+        assert_eq!(0, author().solutions().distinct().limit(0).count());
+        // A grid found before is counted, and its duplicates are skipped.
+        let mut found_author = author();
+        let cw = found_author.complete_cw().unwrap();
+        assert_eq!(0, found_author.solutions().distinct().after(&cw).count());
+    }
+}
@@ -1,74 +1,74 @@
+use author::slots::{AllowedWords, SlotWords};
 use cw::Range;
-use dict::{Dict, PatternIter};
+use dict::{Dict, PatternIter, Word};
 
 /// An iterator over all possibilities to fill one of the given ranges with a word from a set of
-/// dictionaries.
+/// dictionaries. The words are ordered by descending score across all dictionaries. Words with
+/// equal scores are ordered by dictionary, so that the earlier lists are preferred, and then by
+/// the order of the ranges. Only the allowed words are used for ranges in constrained slots.
 pub struct WordRangeIter<'a> {
     ranges: Vec<(Range, Vec<char>)>,
-    dicts: &'a [Dict],
-    range_i: usize,
-    dict_i: usize,
-    pi: Option<PatternIter<'a>>,
+    /// The words matching each range in each dictionary, together with the range's index, ordered
+    /// by dictionary first.
+    pis: Vec<(usize, PatternIter<'a>)>,
 }
 
 impl<'a> WordRangeIter<'a> {
-    pub fn new(ranges: Vec<(Range, Vec<char>)>, dicts: &'a [Dict]) -> WordRangeIter<'a> {
+    pub fn new(ranges: Vec<(Range, Vec<char>)>,
+               dicts: &'a [Dict],
+               slot_words: Option<&SlotWords>)
+               -> WordRangeIter<'a> {
+        let allowed: Vec<Option<AllowedWords>> = ranges
+            .iter()
+            .map(|&(range, _)| slot_words.and_then(|slot_words| slot_words.get(&range)).cloned())
+            .collect();
+        let mut pis = Vec::new();
+        for (dict_i, dict) in dicts.iter().enumerate() {
+            for (range_i, ((_, pattern), allowed)) in ranges.iter().zip(&allowed).enumerate() {
+                let pi = match *allowed {
+                    Some(ref allowed) => dict.matching_words_among(pattern, &allowed[dict_i]),
+                    None => dict.matching_words(pattern),
+                };
+                pis.push((range_i, pi));
+            }
+        }
         WordRangeIter {
             ranges: ranges,
-            dicts: dicts,
-            range_i: 0,
-            dict_i: 0,
-            pi: None,
-        }
-    }
-
-    #[inline]
-    fn get_word(&mut self) -> Option<Vec<char>> {
-        match self.pi {
-            None => None,
-            Some(ref mut iter) => iter.next().cloned(),
+            pis: pis,
         }
     }
 
-    fn advance(&mut self) -> bool {
-        if self.pi.is_some() {
-            self.range_i += 1;
-            if self.range_i >= self.ranges.len() {
-                self.range_i = 0;
-                self.dict_i += 1;
+    /// Returns the index of the pattern iterator whose next word has the highest score, or `None`
+    /// if there are no more words.
+    fn best_index(&self) -> Option<usize> {
+        let mut best = None;
+        for (i, (_, pi)) in self.pis.iter().enumerate() {
+            match (pi.peek_score(), best) {
+                (None, _) => (),
+                (Some(score), Some((_, best_score))) if score <= best_score => (),
+                (Some(score), _) => best = Some((i, score)),
             }
         }
-        if let Some(&(_, ref pattern)) = self.ranges.get(self.range_i) {
-            self.pi = self.dicts
-                .get(self.dict_i)
-                .map(|dict| dict.matching_words(pattern));
-            self.pi.is_some()
-        } else {
-            false
-        }
+        best.map(|(i, _)| i)
     }
 }
 
 impl<'a> Iterator for WordRangeIter<'a> {
-    type Item = (Range, Vec<char>);
+    type Item = (Range, Word<'a>);
 
-    fn next(&mut self) -> Option<(Range, Vec<char>)> {
-        let mut oword = self.get_word();
-        while oword.is_none() && self.advance() {
-            oword = self.get_word();
-        }
-        oword.map(|word| {
-                      let (range, _) = self.ranges[self.range_i];
-                      (range, word)
-                  })
+    fn next(&mut self) -> Option<(Range, Word<'a>)> {
+        self.best_index().and_then(|i| {
+            let (range_i, ref mut pi) = self.pis[i];
+            pi.next().map(|word| (self.ranges[range_i].0, word))
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use author::slots::{SlotConstraint, SlotConstraints};
     use cw::{Dir, Point, Range};
-    use dict::Dict;
     use test_util::*;
 
     #[test]
@@ -92,12 +92,54 @@ mod tests {
                                len: 2,
                            },
                            str_to_cvec("##"))];
-        let dicts = [Dict::new(strs_to_cvecs(&["FAV", "TOOLONG"])),
-                     Dict::new(strs_to_cvecs(&["YO", "FOO", "FOOBAR"]))];
-        let mut iter = WordRangeIter::new(ranges.clone(), &dicts);
-        assert_eq!(Some((ranges[1].0, str_to_cvec("FAV"))), iter.next());
-        assert_eq!(Some((ranges[0].0, str_to_cvec("FOOBAR"))), iter.next());
-        assert_eq!(Some((ranges[1].0, str_to_cvec("FOO"))), iter.next());
-        assert_eq!(Some((ranges[2].0, str_to_cvec("YO"))), iter.next());
+        let dicts = [words_to_dict(strs_to_cvecs(&["FAV", "TOOLONG"])),
+                     words_to_dict(strs_to_cvecs(&["YO", "FOO", "FOOBAR"]))];
+        let mut iter = WordRangeIter::new(ranges.clone(), &dicts, None);
+        let mut next = || iter.next().map(|(range, word)| (range, word.to_vec()));
+        assert_eq!(Some((ranges[1].0, str_to_cvec("FAV"))), next());
+        assert_eq!(Some((ranges[0].0, str_to_cvec("FOOBAR"))), next());
+        assert_eq!(Some((ranges[1].0, str_to_cvec("FOO"))), next());
+        assert_eq!(Some((ranges[2].0, str_to_cvec("YO"))), next());
+        assert_eq!(None, next());
+    }
+
+    #[test]
+    fn test_range_iter_scores() {
+        let point = Point::new(0, 0);
+        let ranges = vec![(Range {
+                               point: point,
+                               dir: Dir::Right,
+                               len: 3,
+                           },
+                           str_to_cvec("###")),
+                          (Range {
+                               point: point,
+                               dir: Dir::Right,
+                               len: 2,
+                           },
+                           str_to_cvec("##"))];
+        let dicts = [scored_dict(vec![(str_to_cvec("FOO"), 20),
+                                      (str_to_cvec("YO"), 40),
+                                      (str_to_cvec("BAR"), 60)])];
+        let words = WordRangeIter::new(ranges.clone(), &dicts, None)
+            .map(|(_, word)| word.to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(strs_to_cvecs(&["BAR", "YO", "FOO"]), words);
+        let mut slots = SlotConstraints::new();
+        slots.insert(point,
+                     Dir::Right,
+                     SlotConstraint::Words(strs_to_cvecs(&["FOO", "YO"]).into_iter().collect()));
+        let slot_words = slots.allowed_words(&dicts);
+        let words = WordRangeIter::new(ranges.clone(), &dicts, Some(&slot_words))
+            .map(|(_, word)| word.to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(strs_to_cvecs(&["YO", "FOO"]), words);
+        // Words from all dictionaries are merged by score, and the earlier one wins ties.
+        let dicts = [scored_dict(vec![(str_to_cvec("FOO"), 20)]),
+                     scored_dict(vec![(str_to_cvec("BAR"), 20), (str_to_cvec("YO"), 40)])];
+        let words = WordRangeIter::new(ranges.clone(), &dicts, None)
+            .map(|(_, word)| word.to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(strs_to_cvecs(&["YO", "FOO", "BAR"]), words);
     }
 }
@@ -0,0 +1,196 @@
+use cw::{Crosswords, Dir, Point, Range};
+use dict::Dict;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// The scheme by which clues and the cells where words begin are labeled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Numbering {
+    /// The cells where any word begins are numbered from left to right, from top to bottom,
+    /// starting with 1. An across and a down word beginning in the same cell share their number.
+    Sequential,
+    /// Across and down words are numbered in two separate sequences, each in reading order.
+    Separate,
+    /// Words are labeled with the coordinates of their first cell: the column as a letter and
+    /// the row as a number, e. g. `B3` for the second column of the third row.
+    Coordinates,
+}
+
+impl FromStr for Numbering {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Numbering, String> {
+        match s {
+            "sequential" => Ok(Numbering::Sequential),
+            "separate" => Ok(Numbering::Separate),
+            "coordinates" => Ok(Numbering::Coordinates),
+            _ => Err(format!("unknown numbering scheme: {}", s)),
+        }
+    }
+}
+
+/// Returns the label of the given cell in the `Coordinates` scheme.
+fn coordinates_label(point: Point) -> String {
+    let mut column = Vec::new();
+    let mut x = point.x as u32 + 1;
+    while x > 0 {
+        column.push((b'A' + ((x - 1) % 26) as u8) as char);
+        x = (x - 1) / 26;
+    }
+    column.into_iter().rev().chain((point.y + 1).to_string().chars()).collect()
+}
+
+/// A word placed in a crosswords grid, together with its clue label and position.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entry {
+    /// The clue label, e. g. the clue number, according to the `Numbering` scheme.
+    pub label: String,
+    /// The direction of the word.
+    pub dir: Dir,
+    /// The cell containing the first letter.
+    pub point: Point,
+    /// The range of cells containing the word.
+    pub range: Range,
+    /// The answer, i. e. the letters of the word.
+    pub word: Vec<char>,
+    /// The original form of the answer, e. g. with spaces or umlauts, as found in the dictionary.
+    /// If it's not in any dictionary, these are just the letters.
+    pub display: String,
+    /// The number of letters in each word of the answer, e. g. `(3,4)` for "New York".
+    pub enumeration: String,
+    /// The index of the first dictionary containing the word, or `None` if not found.
+    pub category: Option<usize>,
+}
+
+impl Crosswords {
+    /// Returns all words in the grid, ordered by their first cell from left to right, from top to
+    /// bottom, and with `Right` before `Down`. They are labeled according to the given scheme, and
+    /// the category, display form and enumeration of each word are looked up in the given
+    /// dictionaries.
+    pub fn entries(&self, dicts: &[Dict], numbering: Numbering) -> Vec<Entry> {
+        let mut entries = Vec::new();
+        let (mut number, mut right_number, mut down_number) = (0, 0, 0);
+        for y in 0..(self.height as i32) {
+            for x in 0..(self.width as i32) {
+                let point = Point::new(x, y);
+                if !self.has_hint_at(point) {
+                    continue;
+                }
+                number += 1;
+                for &dir in &[Dir::Right, Dir::Down] {
+                    if !self.has_hint_at_dir(point, dir) {
+                        continue;
+                    }
+                    let label = match (numbering, dir) {
+                        (Numbering::Sequential, _) => number.to_string(),
+                        (Numbering::Separate, Dir::Right) => {
+                            right_number += 1;
+                            right_number.to_string()
+                        }
+                        (Numbering::Separate, Dir::Down) => {
+                            down_number += 1;
+                            down_number.to_string()
+                        }
+                        (Numbering::Coordinates, _) => coordinates_label(point),
+                    };
+                    let range = self.get_word_range_at(point, dir);
+                    let word: Vec<char> = self.chars(range).collect();
+                    let category = dicts.iter().position(|d| d.contains(&word));
+                    let dict = category.map(|i| &dicts[i]);
+                    let display = dict.and_then(|d| d.get_display_form(&word))
+                        .map_or_else(|| word.iter().collect(), str::to_owned);
+                    let enumeration = dict.and_then(|d| d.get_enumeration(&word))
+                        .unwrap_or_else(|| format!("({})", word.len()));
+                    entries.push(Entry {
+                                     label,
+                                     dir,
+                                     point,
+                                     range,
+                                     word,
+                                     display,
+                                     enumeration,
+                                     category,
+                                 });
+                }
+            }
+        }
+        entries
+    }
+
+    /// Returns the labels to be displayed in the cells where words begin. If an across and a down
+    /// word with different labels begin in the same cell, both are shown, separated by a slash.
+    pub fn cell_labels(&self, numbering: Numbering) -> HashMap<Point, String> {
+        let mut labels: HashMap<Point, String> = HashMap::new();
+        for entry in self.entries(&[], numbering) {
+            let label = labels.entry(entry.point).or_default();
+            if label.is_empty() {
+                *label = entry.label;
+            } else if *label != entry.label {
+                label.push('/');
+                label.push_str(&entry.label);
+            }
+        }
+        labels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::coordinates_label;
+    use cw::{Crosswords, Dir, Numbering, Point};
+    use dict::{Dict, Lang};
+    use test_util::*;
+
+    // ABC
+    // D#E
+    fn create_cw() -> Crosswords {
+        let mut cw = Crosswords::new(3, 2);
+        cw.try_word(Point::new(0, 0), Dir::Right, &str_to_cvec("ABC"));
+        cw.try_word(Point::new(0, 0), Dir::Down, &str_to_cvec("AD"));
+        cw.try_word(Point::new(2, 0), Dir::Down, &str_to_cvec("CE"));
+        cw
+    }
+
+    #[test]
+    fn test_entries() {
+        let cw = create_cw();
+        let dicts = vec![words_to_dict(strs_to_cvecs(&["AD"])),
+                         words_to_dict(strs_to_cvecs(&["ABC"]))];
+        let entries = cw.entries(&dicts, Numbering::Sequential);
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|e| (e.label.as_str(), e.dir, e.point, e.word.clone(), e.category))
+            .collect();
+        assert_eq!(vec![("1", Dir::Right, Point::new(0, 0), str_to_cvec("ABC"), Some(1)),
+                        ("1", Dir::Down, Point::new(0, 0), str_to_cvec("AD"), Some(0)),
+                        ("2", Dir::Down, Point::new(2, 0), str_to_cvec("CE"), None)],
+                   summary);
+        assert_eq!(3, entries[0].range.len);
+        assert_eq!("CE", entries[2].display);
+        assert_eq!("(2)", entries[2].enumeration);
+    }
+
+    #[test]
+    fn test_display_forms() {
+        let cw = create_cw();
+        let entries = vec![(str_to_cvec("ABC"), 50, Some("A-Bc".to_owned()))];
+        let dicts = vec![Dict::from_entries(entries, 3, 1, Lang::English)];
+        let entries = cw.entries(&dicts, Numbering::Sequential);
+        assert_eq!("A-Bc", entries[0].display);
+        assert_eq!("(1-2)", entries[0].enumeration);
+        assert_eq!("AD", entries[1].display);
+    }
+
+    #[test]
+    fn test_numbering() {
+        let cw = create_cw();
+        let labels = |numbering| {
+            cw.entries(&[], numbering).into_iter().map(|e| e.label).collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["1", "1", "2"], labels(Numbering::Separate));
+        assert_eq!(vec!["A1", "A1", "C1"], labels(Numbering::Coordinates));
+        assert_eq!(Some(&"1".to_owned()),
+                   cw.cell_labels(Numbering::Separate).get(&Point::new(0, 0)));
+        assert_eq!("AB12", coordinates_label(Point::new(27, 11)));
+    }
+}
@@ -1,11 +1,17 @@
 mod boundary_iter;
+mod entry;
 mod point_iter;
 mod print_iter;
 mod range_iter;
 mod ranges_iter;
 mod point;
 mod range;
+mod symmetry;
+mod text;
+mod transform;
+mod validate;
 
+pub use cw::entry::{Entry, Numbering};
 pub use cw::point_iter::PointIter;
 pub use cw::print_iter::PrintItem;
 pub use cw::range::Range;
@@ -49,7 +55,7 @@ impl Dir {
 }
 
 /// A crosswords grid that keeps track of the words it contains and doesn't allow duplicates.
-#[derive(Clone)]
+#[derive(Clone, Eq, PartialEq)]
 pub struct Crosswords {
     width: usize,
     height: usize,
@@ -404,9 +410,10 @@ impl Crosswords {
 
     /// Returns an iterator over the `PrintItem`s representing the current state of the crosswords,
     /// including all borders and cell contents, from left to right, from top to bottom. They can
-    /// be converted to text or graphics to display the grid.
-    pub fn print_items(&self) -> PrintIter {
-        PrintIter::new(self)
+    /// be converted to text or graphics to display the grid. Cells are labeled according to the
+    /// given numbering scheme.
+    pub fn print_items(&self, numbering: Numbering) -> PrintIter {
+        PrintIter::new(self, numbering)
     }
 
     /// Returns an iterator over all pairs of points that define the border of the cluster of empty
@@ -425,7 +432,7 @@ impl Display for Crosswords {
             let br = 100. * (bc as f32) / (bt as f32);
             try!(formatter.write_fmt(format_args!("{} / {} borders ({}%)\n", bc, bt, br)));
         }
-        for item in self.print_items() {
+        for item in self.print_items(Numbering::Sequential) {
             try!(formatter.write_str(&match item {
                                               PrintItem::Cross(true) => '\u{00B7}',
                                               PrintItem::VertBorder(true) => '|',
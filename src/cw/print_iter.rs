@@ -1,4 +1,5 @@
-use cw::{BLOCK, Crosswords, Dir, Point};
+use cw::{BLOCK, Crosswords, Dir, Numbering, Point};
+use std::collections::HashMap;
 
 /// An element representing a part of a crosswords grid: an element of the cell's borders, a cell
 /// and its contents or a line break. It should be converted to a textual or graphical
@@ -18,9 +19,9 @@ pub enum PrintItem {
     /// A solid block that is left empty in the crossword's solution. It does not belong to a word.
     Block,
     /// A cell that belongs to one or two words and contains the given character. If one or two
-    /// words begin in this cell, the second value will be `n`, where this is the `n`-th cell
-    /// containing the beginning of a word.
-    CharHint(char, Option<u32>),
+    /// words begin in this cell, the second value will be their label, as given by
+    /// `Crosswords::cell_labels`.
+    CharHint(char, Option<String>),
     /// A line break. This follows after every row of borders or cells.
     LineBreak,
 }
@@ -31,17 +32,17 @@ pub struct PrintIter<'a> {
     between_lines: bool,
     between_chars: bool,
     cw: &'a Crosswords,
-    hint_count: u32,
+    labels: HashMap<Point, String>,
 }
 
 impl<'a> PrintIter<'a> {
-    pub fn new(cw: &'a Crosswords) -> Self {
+    pub fn new(cw: &'a Crosswords, numbering: Numbering) -> Self {
         PrintIter {
             point: Point::new(-1, -1),
             between_lines: true,
             between_chars: true,
             cw: cw,
-            hint_count: 0,
+            labels: cw.cell_labels(numbering),
         }
     }
 }
@@ -90,15 +91,7 @@ impl<'a> Iterator for PrintIter<'a> {
             } else {
                 result = match self.cw.get_char(self.point).unwrap() {
                     BLOCK => PrintItem::Block,
-                    c => {
-                        PrintItem::CharHint(c,
-                                            if self.cw.has_hint_at(self.point) {
-                                                self.hint_count += 1;
-                                                Some(self.hint_count)
-                                            } else {
-                                                None
-                                            })
-                    }
+                    c => PrintItem::CharHint(c, self.labels.get(&self.point).cloned()),
                 };
             }
             self.between_chars = true;
@@ -0,0 +1,84 @@
+use cw::Crosswords;
+use std::hash::{Hash, Hasher};
+
+/// The hash only depends on the grid's contents, since the set of words is determined by them.
+impl Hash for Crosswords {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.height.hash(state);
+        self.chars.hash(state);
+        self.right_border.hash(state);
+        self.down_border.hash(state);
+    }
+}
+
+impl Crosswords {
+    /// Returns the canonical representative of the grid's class under transposition: Two grids
+    /// have the same canonical form if and only if they are equal or one is the transposition of
+    /// the other. Rotations and other reflections are not taken into account, since they reverse
+    /// some of the words, so that the rotated grid consists of different words.
+    pub fn canonical(&self) -> Crosswords {
+        let transposed = self.transposed();
+        if transposed.to_text() < self.to_text() {
+            transposed
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Returns the number of words the grids have in common, divided by the number of words that
+    /// are contained in at least one of them. This is 1 for grids with the same set of words, and
+    /// 0 for grids that have no word in common.
+    pub fn word_overlap(&self, other: &Crosswords) -> f32 {
+        let common = self.words.intersection(&other.words).count();
+        let total = self.words.len() + other.words.len() - common;
+        if total == 0 {
+            1.
+        } else {
+            common as f32 / total as f32
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cw::{Crosswords, Dir, Point};
+    use std::collections::HashSet;
+    use test_util::*;
+
+    // AB#
+    // C##
+    fn create_cw() -> Crosswords {
+        let mut cw = Crosswords::new(3, 2);
+        cw.try_word(Point::new(0, 0), Dir::Right, &str_to_cvec("AB"));
+        cw.try_word(Point::new(0, 0), Dir::Down, &str_to_cvec("AC"));
+        cw
+    }
+
+    #[test]
+    fn test_canonical() {
+        let cw = create_cw();
+        assert!(cw != cw.transposed());
+        assert!(cw == cw.transposed().transposed());
+        assert!(cw.canonical() == cw.transposed().canonical());
+        // Rotating by 180 degrees reverses the words: The grid contains BA and CA instead.
+        let rotated = cw.rotated().rotated();
+        assert!(rotated.get_words().contains(&str_to_cvec("BA")));
+        assert!(cw.canonical() != rotated.canonical());
+        let mirrored = cw.mirrored(Dir::Right);
+        assert!(cw.canonical() != mirrored.canonical());
+        let canonical: HashSet<Crosswords> =
+            [cw.clone(), cw.transposed(), rotated].iter().map(Crosswords::canonical).collect();
+        assert_eq!(2, canonical.len());
+    }
+
+    #[test]
+    fn test_word_overlap() {
+        let cw = create_cw();
+        let mut other = create_cw();
+        assert_eq!(1., cw.word_overlap(&other));
+        other.try_word(Point::new(1, 0), Dir::Down, &str_to_cvec("BD"));
+        assert_eq!(2. / 3., cw.word_overlap(&other));
+        assert_eq!(1. / 3., cw.word_overlap(&cw.mirrored(Dir::Right)));
+    }
+}
@@ -0,0 +1,74 @@
+use cw::{BLOCK, Crosswords, Numbering, PrintItem};
+
+impl Crosswords {
+    /// Returns a plain text representation of the grid that can be read with `from_text`. Every
+    /// other line contains the cells, with `#` for blocks and `|` for vertical borders between
+    /// different words. The lines in between contain the horizontal borders, drawn as `-`, and the
+    /// crossing points, drawn as `+`.
+    pub fn to_text(&self) -> String {
+        self.print_items(Numbering::Sequential)
+            .map(|item| match item {
+                     PrintItem::Cross(true) => '+',
+                     PrintItem::VertBorder(true) => '|',
+                     PrintItem::HorizBorder(true) => '-',
+                     PrintItem::Cross(false) |
+                     PrintItem::VertBorder(false) |
+                     PrintItem::HorizBorder(false) => ' ',
+                     PrintItem::Block => BLOCK,
+                     PrintItem::CharHint(c, _) => c,
+                     PrintItem::LineBreak => '\n',
+                 })
+            .collect()
+    }
+
+    /// Reads a grid in the format produced by `to_text`. The set of words is computed from the
+    /// letters and borders.
+    pub fn from_text(text: &str) -> Result<Crosswords, String> {
+        let lines: Vec<Vec<char>> = text.lines()
+            .map(|line| line.trim_end_matches('\r').chars().collect())
+            .filter(|line: &Vec<char>| !line.is_empty())
+            .collect();
+        let height = lines.len().saturating_sub(1) / 2;
+        let width = lines.first().map_or(0, |line| line.len().saturating_sub(1) / 2);
+        if width == 0 || height == 0 || lines.len() != 2 * height + 1 {
+            return Err("the grid must have an odd number of at least 3 lines".to_owned());
+        }
+        if let Some(i) = lines.iter().position(|line| line.len() != 2 * width + 1) {
+            return Err(format!("line {} should have {} characters", i + 1, 2 * width + 1));
+        }
+        let mut cw = Crosswords::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                cw.chars[x + width * y] = lines[2 * y + 1][2 * x + 1];
+                if x + 1 < width {
+                    cw.right_border[x + (width - 1) * y] = lines[2 * y + 1][2 * x + 2] != ' ';
+                }
+                if y + 1 < height {
+                    cw.down_border[x + width * y] = lines[2 * y + 2][2 * x + 1] != ' ';
+                }
+            }
+        }
+        cw.words = cw.word_ranges().map(|range| cw.chars(range).collect()).collect();
+        Ok(cw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cw::{Crosswords, Dir, Point};
+    use test_util::*;
+
+    #[test]
+    fn test_text() {
+        let mut cw = Crosswords::new(3, 2);
+        cw.try_word(Point::new(0, 0), Dir::Right, &str_to_cvec("AB"));
+        cw.try_word(Point::new(0, 0), Dir::Down, &str_to_cvec("AC"));
+        cw.try_word(Point::new(2, 0), Dir::Down, &str_to_cvec("DE"));
+        let text = cw.to_text();
+        assert_eq!("+-+-+-+\n|A B|D|\n+ +-+ +\n|C|#|E|\n+-+-+-+\n", text);
+        let read_cw = Crosswords::from_text(&text).unwrap();
+        assert_eq!(text, read_cw.to_text());
+        assert_eq!(cw.get_words(), read_cw.get_words());
+        assert!(Crosswords::from_text("+-+-+\n|A|\n+-+-+\n").is_err());
+    }
+}
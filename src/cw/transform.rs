@@ -0,0 +1,140 @@
+use cw::{BLOCK, Crosswords, Dir, Point, PointIter, Range};
+use std::cmp;
+
+impl Crosswords {
+    /// Returns a grid of the given dimensions which contains the letter of each cell `p` of this
+    /// grid in cell `f(p)`. The map `f` must take every range of cells to a range in the new grid,
+    /// and must take all cells belonging to a word to cells inside the new grid.
+    fn remapped<F>(&self, width: usize, height: usize, f: F) -> Crosswords
+        where F: Fn(Point) -> Point
+    {
+        let mut cw = Crosswords::new(width, height);
+        for range in self.word_ranges() {
+            let p0 = f(range.point);
+            let p1 = f(range.point + range.dir.point() * (range.len - 1));
+            let new_range = Range {
+                point: Point::new(cmp::min(p0.x, p1.x), cmp::min(p0.y, p1.y)),
+                dir: if p0.y == p1.y { Dir::Right } else { Dir::Down },
+                len: range.len,
+            };
+            for (c, p) in self.chars(range).zip(range.points()) {
+                cw.put_char(f(p), c);
+            }
+            for p in PointIter::new(new_range.point, new_range.dir, range.len - 1) {
+                cw.set_border(p, new_range.dir, false);
+            }
+        }
+        cw.words = cw.word_ranges().map(|range| cw.chars(range).collect()).collect();
+        cw
+    }
+
+    /// Returns the grid reflected along its main diagonal: Rows become columns and vice versa.
+    /// Words in direction `Right` become words in direction `Down` and vice versa.
+    pub fn transposed(&self) -> Crosswords {
+        self.remapped(self.height, self.width, |p| Point::new(p.y, p.x))
+    }
+
+    /// Returns the grid rotated clockwise by 90 degrees. Note that this reverses the words which
+    /// were in direction `Down` and are now in direction `Right`.
+    pub fn rotated(&self) -> Crosswords {
+        let h = self.height as i32;
+        self.remapped(self.height, self.width, |p| Point::new(h - 1 - p.y, p.x))
+    }
+
+    /// Returns the grid mirrored so that the order of the cells in the given direction is
+    /// reversed. Note that this reverses the words in that direction.
+    pub fn mirrored(&self, dir: Dir) -> Crosswords {
+        let (w, h) = (self.width as i32, self.height as i32);
+        match dir {
+            Dir::Right => self.remapped(self.width, self.height, |p| Point::new(w - 1 - p.x, p.y)),
+            Dir::Down => self.remapped(self.width, self.height, |p| Point::new(p.x, h - 1 - p.y)),
+        }
+    }
+
+    /// Returns the grid with the given numbers of empty columns and rows added to the left, top,
+    /// right and bottom.
+    pub fn padded(&self, left: usize, top: usize, right: usize, bottom: usize) -> Crosswords {
+        let offset = Point::new(left as i32, top as i32);
+        self.remapped(self.width + left + right,
+                      self.height + top + bottom,
+                      |p| p + offset)
+    }
+
+    /// Returns the grid cropped to the smallest rectangle containing all letters. If the grid is
+    /// empty, an unchanged copy is returned.
+    pub fn cropped(&self) -> Crosswords {
+        let letters: Vec<Point> = (0..(self.width * self.height))
+            .filter(|&i| self.chars[i] != BLOCK)
+            .map(|i| Point::new((i % self.width) as i32, (i / self.width) as i32))
+            .collect();
+        if letters.is_empty() {
+            return self.clone();
+        }
+        let min_x = letters.iter().map(|p| p.x).min().unwrap();
+        let max_x = letters.iter().map(|p| p.x).max().unwrap();
+        let min_y = letters.iter().map(|p| p.y).min().unwrap();
+        let max_y = letters.iter().map(|p| p.y).max().unwrap();
+        let offset = Point::new(min_x, min_y);
+        self.remapped((max_x - min_x + 1) as usize,
+                      (max_y - min_y + 1) as usize,
+                      |p| p - offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cw::{Crosswords, Dir, Point};
+    use std::collections::HashSet;
+    use test_util::*;
+
+    // ##AB#
+    // ##C##
+    fn create_cw() -> Crosswords {
+        let mut cw = Crosswords::new(5, 2);
+        cw.try_word(Point::new(2, 0), Dir::Right, &str_to_cvec("AB"));
+        cw.try_word(Point::new(2, 0), Dir::Down, &str_to_cvec("AC"));
+        cw
+    }
+
+    fn words(cw: &Crosswords) -> HashSet<Vec<char>> {
+        cw.get_words().clone()
+    }
+
+    #[test]
+    fn test_transposed() {
+        let cw = create_cw();
+        let tcw = cw.transposed();
+        assert_eq!((2, 5), (tcw.get_width(), tcw.get_height()));
+        assert_eq!(str_to_cvec("AB"), tcw.word_at(Point::new(0, 2), Dir::Down));
+        assert_eq!(str_to_cvec("AC"), tcw.word_at(Point::new(0, 2), Dir::Right));
+        assert_eq!(words(&cw), words(&tcw));
+        assert_eq!(cw.count_borders(), tcw.count_borders());
+        let ttcw = tcw.transposed();
+        assert_eq!(cw.to_string(), ttcw.to_string());
+    }
+
+    #[test]
+    fn test_rotated_and_mirrored() {
+        let cw = create_cw();
+        let rcw = cw.rotated();
+        assert_eq!(str_to_cvec("CA"), rcw.word_at(Point::new(0, 2), Dir::Right));
+        assert_eq!(str_to_cvec("AB"), rcw.word_at(Point::new(1, 2), Dir::Down));
+        let mcw = cw.mirrored(Dir::Right);
+        assert_eq!(str_to_cvec("BA"), mcw.word_at(Point::new(1, 0), Dir::Right));
+        assert_eq!(str_to_cvec("AC"), mcw.word_at(Point::new(2, 0), Dir::Down));
+        let rrcw = cw.rotated().rotated();
+        assert_eq!(cw.to_string(),
+                   rrcw.mirrored(Dir::Down).mirrored(Dir::Right).to_string());
+    }
+
+    #[test]
+    fn test_cropped_and_padded() {
+        let cw = create_cw();
+        let ccw = cw.cropped();
+        assert_eq!((2, 2), (ccw.get_width(), ccw.get_height()));
+        assert_eq!(str_to_cvec("AB"), ccw.word_at(Point::new(0, 0), Dir::Right));
+        assert_eq!(words(&cw), words(&ccw));
+        let pcw = ccw.padded(2, 0, 1, 0);
+        assert_eq!(cw.to_string(), pcw.to_string());
+    }
+}
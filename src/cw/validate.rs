@@ -0,0 +1,161 @@
+use cw::{BLOCK, Crosswords, Dir, Point, Range};
+use dict::Dict;
+use std::cmp;
+use std::collections::HashSet;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// A violation of the consistency of a crosswords grid or of the requirements for its words.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Violation {
+    /// There is no border between a block and an adjacent cell in the given direction.
+    MissingBorder(Point, Dir),
+    /// The letter in that cell does not belong to any word.
+    IsolatedLetter(Point),
+    /// The letters in the range are not recorded as a word of the grid.
+    UnrecordedWord(Range),
+    /// The word in the range appears elsewhere in the grid, too.
+    DuplicateWord(Range),
+    /// The recorded word does not appear in the grid.
+    MissingWord(Vec<char>),
+    /// The word in the range is not contained in any of the dictionaries.
+    UnknownWord(Range),
+    /// The word in the range shares only the first number of letters with perpendicular words,
+    /// but the second number is required.
+    TooFewCrossings(Range, usize, usize),
+}
+
+fn format_range(range: &Range) -> String {
+    format!("({}, {}) {:?}", range.point.x, range.point.y, range.dir)
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Violation::MissingBorder(p, dir) => {
+                write!(f, "({}, {}) {:?}: missing border next to a block", p.x, p.y, dir)
+            }
+            Violation::IsolatedLetter(p) => {
+                write!(f, "({}, {}): letter does not belong to a word", p.x, p.y)
+            }
+            Violation::UnrecordedWord(ref r) => {
+                write!(f, "{}: word is not recorded", format_range(r))
+            }
+            Violation::DuplicateWord(ref r) => {
+                write!(f, "{}: word appears more than once", format_range(r))
+            }
+            Violation::MissingWord(ref word) => {
+                write!(f, "recorded word {} is not in the grid", word.iter().collect::<String>())
+            }
+            Violation::UnknownWord(ref r) => {
+                write!(f, "{}: word is not in any dictionary", format_range(r))
+            }
+            Violation::TooFewCrossings(ref r, actual, required) => {
+                write!(f,
+                       "{}: {} letters are crossed by other words, {} required",
+                       format_range(r),
+                       actual,
+                       required)
+            }
+        }
+    }
+}
+
+impl Crosswords {
+    /// Returns the number of letters in a word of the given length that must be shared with a
+    /// perpendicular word.
+    pub fn required_crossings(len: usize,
+                              min_crossing: usize,
+                              min_crossing_percent: usize)
+                              -> usize {
+        if min_crossing > len {
+            return 0;
+        }
+        cmp::max(min_crossing_percent * len / 100, min_crossing)
+    }
+
+    /// Checks the grid for consistency and returns all violations found: Every letter must belong
+    /// to a word, each word must be recorded exactly once and appear in one of the dictionaries,
+    /// and it must cross enough perpendicular words to satisfy `min_crossing` and
+    /// `min_crossing_percent`.
+    pub fn validate(&self,
+                    dicts: &[Dict],
+                    min_crossing: usize,
+                    min_crossing_percent: usize)
+                    -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for y in 0..(self.height as i32) {
+            for x in 0..(self.width as i32) {
+                let p = Point::new(x, y);
+                for &dir in &[Dir::Right, Dir::Down] {
+                    if !self.get_border(p, dir) &&
+                       (!self.is_letter(p) || !self.is_letter(p + dir.point())) {
+                        violations.push(Violation::MissingBorder(p, dir));
+                    }
+                }
+                if self.is_letter(p) && self.both_borders(p, Dir::Right) &&
+                   self.both_borders(p, Dir::Down) {
+                    violations.push(Violation::IsolatedLetter(p));
+                }
+            }
+        }
+        let mut seen = HashSet::new();
+        for range in self.word_ranges() {
+            let word: Vec<char> = self.chars(range).collect();
+            if word.contains(&BLOCK) {
+                continue; // Already reported as a missing border.
+            }
+            if !self.words.contains(&word) {
+                violations.push(Violation::UnrecordedWord(range));
+            }
+            if !seen.insert(word.clone()) {
+                violations.push(Violation::DuplicateWord(range));
+            }
+            if !dicts.iter().any(|dict| dict.contains(&word)) {
+                violations.push(Violation::UnknownWord(range));
+            }
+            let odir = range.dir.other();
+            let crossings = range.points().filter(|&p| !self.both_borders(p, odir)).count();
+            let required = Crosswords::required_crossings(range.len,
+                                                          min_crossing,
+                                                          min_crossing_percent);
+            if crossings < required {
+                violations.push(Violation::TooFewCrossings(range, crossings, required));
+            }
+        }
+        for word in self.words.iter().filter(|word| !seen.contains(*word)) {
+            violations.push(Violation::MissingWord(word.clone()));
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Violation;
+    use cw::{Crosswords, Dir, Point, Range};
+    use test_util::*;
+
+    #[test]
+    fn test_validate() {
+        let mut cw = Crosswords::new(3, 2);
+        cw.try_word(Point::new(0, 0), Dir::Right, &str_to_cvec("ABC"));
+        cw.try_word(Point::new(0, 0), Dir::Down, &str_to_cvec("AD"));
+        let dicts = vec![words_to_dict(strs_to_cvecs(&["ABC", "AD"]))];
+        assert_eq!(Vec::<Violation>::new(), cw.validate(&dicts, 1, 0));
+        let range = Range {
+            point: Point::new(0, 0),
+            dir: Dir::Right,
+            len: 3,
+        };
+        assert_eq!(vec![Violation::TooFewCrossings(range, 1, 3)], cw.validate(&dicts, 3, 0));
+        cw.right_border[0] = true;
+        let violations = cw.validate(&dicts, 1, 0);
+        assert!(violations.contains(&Violation::MissingWord(str_to_cvec("ABC"))));
+        assert!(violations.contains(&Violation::UnknownWord(Range {
+                                                                 point: Point::new(1, 0),
+                                                                 len: 2,
+                                                                 ..range
+                                                             })));
+    }
+}
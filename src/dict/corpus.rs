@@ -0,0 +1,103 @@
+//! Building word lists from text corpora: The texts are split into tokens at every character that
+//! is not a letter, and the number of occurrences of each normalized word determines its score.
+
+use dict::{DEFAULT_SCORE, Dict, Lang, WordEntry};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Result};
+use std::path::Path;
+
+/// Counts how often each token occurs in a collection of texts.
+pub struct WordCounts {
+    counts: HashMap<String, usize>,
+}
+
+impl WordCounts {
+    /// Creates an empty collection.
+    pub fn new() -> WordCounts {
+        WordCounts { counts: HashMap::new() }
+    }
+
+    /// Counts the tokens in the text. Words containing apostrophes or hyphens are split into
+    /// separate tokens.
+    pub fn add_text(&mut self, text: &str) {
+        for token in text.split(|c: char| !c.is_alphabetic()).filter(|token| !token.is_empty()) {
+            if let Some(count) = self.counts.get_mut(token) {
+                *count += 1;
+                continue;
+            }
+            self.counts.insert(token.to_owned(), 1);
+        }
+    }
+
+    /// Counts the tokens in the text file, line by line, so that large corpora don't have to fit
+    /// into memory. Lines that are not valid UTF-8 are skipped.
+    pub fn read_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut buf = Vec::new();
+        while reader.read_until(b'\n', &mut buf)? > 0 {
+            if let Ok(line) = ::std::str::from_utf8(&buf) {
+                self.add_text(line);
+            }
+            buf.clear();
+        }
+        Ok(())
+    }
+
+    /// Returns the number of tokens counted so far.
+    pub fn token_count(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Returns the entries for all normalized words that occur at least `min_count` times, in no
+    /// particular order. Tokens that can't be normalized are skipped. Tokens that only differ in
+    /// case or spelling, e. g. `Mäuse` and `maeuse` in German, are counted as the same word,
+    /// whose display form is its most frequent spelling.
+    ///
+    /// The scores grow logarithmically with the number of occurrences, from 0 for words that occur
+    /// only once to 100 for the most frequent word.
+    pub fn entries(&self, lang: Lang, min_count: usize) -> Vec<WordEntry> {
+        let mut words: HashMap<Vec<char>, (usize, usize, &str, Option<String>)> = HashMap::new();
+        for (token, &count) in &self.counts {
+            let (word, _, display) = match Dict::parse_line(token, lang) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            let entry = words.entry(word).or_insert((0, 0, token.as_str(), None));
+            entry.0 += count;
+            if (count, &token[..]) > (entry.1, entry.2) {
+                *entry = (entry.0, count, token, display);
+            }
+        }
+        let max_count = words.values().map(|entry| entry.0).max().unwrap_or(0);
+        let score = |count: usize| if max_count > 1 {
+            (100. * (count as f64).ln() / (max_count as f64).ln()).round() as i32
+        } else {
+            DEFAULT_SCORE
+        };
+        words.into_iter()
+            .filter(|&(_, (count, _, _, _))| count >= min_count)
+            .map(|(word, (count, _, _, display))| (word, score(count), display))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::*;
+
+    #[test]
+    fn test_entries() {
+        let mut counts = WordCounts::new();
+        counts.add_text("Die Mäuse, die maeuse! Die Maus:\n");
+        counts.add_text("die 3 Mäuse, die Straße; l'été");
+        assert_eq!(12, counts.token_count());
+        let mut entries = counts.entries(Lang::German, 2);
+        entries.sort();
+        assert_eq!(vec![(str_to_cvec("DIE"), 100, None),
+                        (str_to_cvec("MAEUSE"), 68, Some("Mäuse".to_owned()))],
+                   entries);
+        assert_eq!(6, counts.entries(Lang::German, 1).len());
+    }
+}
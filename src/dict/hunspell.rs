@@ -0,0 +1,391 @@
+//! Import of Hunspell dictionaries: A `.dic` file lists the base forms of the words with flags,
+//! and the `.aff` file next to it defines which prefixes and suffixes the flags stand for.
+//!
+//! Only the features relevant for generating the list of valid words are supported: `SET`,
+//! `FLAG`, `PFX`, `SFX` (including continuation classes), `FORBIDDENWORD`, `NEEDAFFIX` and
+//! `ONLYINCOMPOUND`. Compounding rules are ignored.
+
+use dict::Dict;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Result};
+use std::path::{Path, PathBuf};
+
+fn invalid_data(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// Reads the file and decodes it with the given encoding, as specified by the `SET` option.
+fn read_file(path: &Path, encoding: &str) -> Result<String> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    match encoding {
+        "UTF-8" => String::from_utf8(bytes).map_err(|err| invalid_data(err.to_string())),
+        "ISO8859-1" => Ok(bytes.into_iter().map(|b| b as char).collect()),
+        "ISO8859-15" => {
+            Ok(bytes.into_iter()
+                   .map(|b| match b {
+                            0xA4 => '€',
+                            0xA6 => 'Š',
+                            0xA8 => 'š',
+                            0xB4 => 'Ž',
+                            0xB8 => 'ž',
+                            0xBC => 'Œ',
+                            0xBD => 'œ',
+                            0xBE => 'Ÿ',
+                            b => b as char,
+                        })
+                   .collect())
+        }
+        _ => Err(invalid_data(format!("unsupported encoding: {}", encoding))),
+    }
+}
+
+/// Returns the value of the `SET` option in the affix file, which must be ASCII-compatible.
+fn read_encoding(aff_path: &Path) -> Result<String> {
+    let mut bytes = Vec::new();
+    File::open(aff_path)?.read_to_end(&mut bytes)?;
+    let text = String::from_utf8_lossy(&bytes);
+    Ok(text.lines()
+           .filter_map(|line| {
+                           let mut parts = line.split_whitespace();
+                           match (parts.next(), parts.next()) {
+                               (Some("SET"), Some(encoding)) => Some(encoding.to_uppercase()),
+                               _ => None,
+                           }
+                       })
+           .next()
+           .unwrap_or_else(|| "ISO8859-1".to_owned()))
+}
+
+/// The way flags are written in the dictionary and affix files.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum FlagType {
+    /// Each character is a flag.
+    Char,
+    /// Each pair of characters is a flag.
+    Long,
+    /// Flags are decimal numbers, separated by commas.
+    Num,
+}
+
+impl FlagType {
+    fn parse(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagType::Char => flags.chars().map(|c| c.to_string()).collect(),
+            FlagType::Long => {
+                let chars: Vec<char> = flags.chars().collect();
+                chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+            }
+            FlagType::Num => flags.split(',').map(|flag| flag.trim().to_owned()).collect(),
+        }
+    }
+}
+
+/// One element of an affix condition.
+#[derive(Debug)]
+enum CondElem {
+    Any,
+    Char(char),
+    Set(Vec<char>, bool),
+}
+
+impl CondElem {
+    fn matches(&self, c: char) -> bool {
+        match *self {
+            CondElem::Any => true,
+            CondElem::Char(d) => c == d,
+            CondElem::Set(ref set, negated) => set.contains(&c) != negated,
+        }
+    }
+}
+
+/// Parses an affix condition like `[^aeiou]y`.
+fn parse_condition(condition: &str) -> Vec<CondElem> {
+    let mut result = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        result.push(match c {
+                        '.' => CondElem::Any,
+                        '[' => {
+                            let mut set: Vec<char> =
+                                chars.by_ref().take_while(|&c| c != ']').collect();
+                            let negated = set.first() == Some(&'^');
+                            if negated {
+                                set.remove(0);
+                            }
+                            CondElem::Set(set, negated)
+                        }
+                        c => CondElem::Char(c),
+                    });
+    }
+    result
+}
+
+/// A prefix or suffix rule.
+#[derive(Debug)]
+struct Affix {
+    strip: Vec<char>,
+    add: Vec<char>,
+    condition: Vec<CondElem>,
+    /// The flags of the affixed word, i. e. affixes that can be applied on top of this one.
+    flags: Vec<String>,
+}
+
+impl Affix {
+    /// Applies the rule to the word, or returns `None` if its condition is not satisfied.
+    fn apply(&self, word: &[char], prefix: bool) -> Option<Vec<char>> {
+        let len = self.condition.len();
+        if word.len() < len || word.len() <= self.strip.len() {
+            return None;
+        }
+        if prefix {
+            if !word.starts_with(&self.strip) ||
+               !self.condition.iter().zip(word).all(|(elem, &c)| elem.matches(c)) {
+                return None;
+            }
+            Some(self.add.iter().chain(&word[self.strip.len()..]).cloned().collect())
+        } else {
+            if !word.ends_with(&self.strip) ||
+               !self.condition
+                    .iter()
+                    .zip(&word[(word.len() - len)..])
+                    .all(|(elem, &c)| elem.matches(c)) {
+                return None;
+            }
+            let stem = &word[..(word.len() - self.strip.len())];
+            Some(stem.iter().chain(&self.add).cloned().collect())
+        }
+    }
+}
+
+/// All rules with the same flag.
+#[derive(Debug)]
+struct AffixClass {
+    prefix: bool,
+    cross_product: bool,
+    rules: Vec<Affix>,
+}
+
+/// The contents of an affix file.
+struct Affixes {
+    flag_type: FlagType,
+    classes: HashMap<String, AffixClass>,
+    forbidden: Option<String>,
+    need_affix: Option<String>,
+    only_in_compound: Option<String>,
+}
+
+impl Affixes {
+    fn parse(text: &str) -> Result<Affixes> {
+        let mut affixes = Affixes {
+            flag_type: FlagType::Char,
+            classes: HashMap::new(),
+            forbidden: None,
+            need_affix: None,
+            only_in_compound: None,
+        };
+        for line in text.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match (parts.first().cloned(), parts.len()) {
+                (Some("FLAG"), 2) => {
+                    affixes.flag_type = match parts[1] {
+                        "long" => FlagType::Long,
+                        "num" => FlagType::Num,
+                        _ => FlagType::Char,
+                    }
+                }
+                (Some("FORBIDDENWORD"), 2) => affixes.forbidden = Some(parts[1].to_owned()),
+                (Some("NEEDAFFIX"), 2) |
+                (Some("PSEUDOROOT"), 2) => affixes.need_affix = Some(parts[1].to_owned()),
+                (Some("ONLYINCOMPOUND"), 2) => {
+                    affixes.only_in_compound = Some(parts[1].to_owned())
+                }
+                (Some(kind @ "PFX"), 4) |
+                (Some(kind @ "SFX"), 4) if !affixes.classes.contains_key(parts[1]) => {
+                    affixes.classes.insert(parts[1].to_owned(),
+                                           AffixClass {
+                                               prefix: kind == "PFX",
+                                               cross_product: parts[2] == "Y",
+                                               rules: Vec::new(),
+                                           });
+                }
+                (Some("PFX"), n) |
+                (Some("SFX"), n) if n >= 4 => {
+                    let flag_type = affixes.flag_type;
+                    let class = affixes.classes
+                        .get_mut(parts[1])
+                        .ok_or_else(|| invalid_data(format!("undeclared affix: {}", line)))?;
+                    let mut add_parts = parts[3].splitn(2, '/');
+                    let add = add_parts.next().unwrap_or("");
+                    let flags = add_parts.next().map_or_else(Vec::new, |f| flag_type.parse(f));
+                    let to_chars = |s: &str| if s == "0" { vec![] } else { s.chars().collect() };
+                    let condition = parse_condition(parts.get(4).cloned().unwrap_or("."));
+                    class.rules.push(Affix {
+                                         strip: to_chars(parts[2]),
+                                         add: to_chars(add),
+                                         condition,
+                                         flags,
+                                     });
+                }
+                _ => (),
+            }
+        }
+        Ok(affixes)
+    }
+
+    fn has(&self, flags: &[String], flag: &Option<String>) -> bool {
+        flag.iter().any(|flag| flags.contains(flag))
+    }
+
+    /// Returns all forms of the word with the given flags, including the base form unless it
+    /// needs an affix. A suffix' continuation flags are applied once more, and prefixes are
+    /// combined with suffixes if both allow cross products.
+    fn expand(&self, word: &[char], flags: &[String], result: &mut Vec<Vec<char>>) {
+        if !self.has(flags, &self.need_affix) {
+            result.push(word.to_vec());
+        }
+        let classes: Vec<&AffixClass> = flags.iter().filter_map(|f| self.classes.get(f)).collect();
+        let mut suffixed = Vec::new();
+        for class in classes.iter().filter(|class| !class.prefix) {
+            for rule in &class.rules {
+                if let Some(form) = rule.apply(word, false) {
+                    let cont_classes = rule.flags.iter().filter_map(|f| self.classes.get(f));
+                    let cont_rules: Vec<&Affix> = cont_classes.filter(|class| !class.prefix)
+                        .flat_map(|class| &class.rules)
+                        .collect();
+                    if !self.has(&rule.flags, &self.need_affix) {
+                        result.push(form.clone());
+                    }
+                    result.extend(cont_rules.iter().filter_map(|rule| rule.apply(&form, false)));
+                    if class.cross_product {
+                        suffixed.push(form);
+                    }
+                }
+            }
+        }
+        for class in classes.iter().filter(|class| class.prefix) {
+            for rule in &class.rules {
+                result.extend(rule.apply(word, true));
+                if class.cross_product {
+                    result.extend(suffixed.iter().filter_map(|form| rule.apply(form, true)));
+                }
+            }
+        }
+    }
+}
+
+/// Returns the path of the affix file if the given path is a `.dic` file with an `.aff` file next
+/// to it.
+fn affix_path(path: &Path) -> Option<PathBuf> {
+    if path.extension() != Some("dic".as_ref()) {
+        return None;
+    }
+    Some(path.with_extension("aff")).filter(|aff_path| aff_path.is_file())
+}
+
+impl Dict {
+    /// Returns whether the given path is a Hunspell dictionary, i. e. a `.dic` file with an `.aff`
+    /// file next to it.
+    pub fn is_hunspell_file<P: AsRef<Path>>(path: P) -> bool {
+        affix_path(path.as_ref()).is_some()
+    }
+
+    /// Reads a Hunspell dictionary and returns all words it defines, in the order of their base
+    /// forms. If `base_only` is true, affixes are not applied. Words flagged as forbidden are
+    /// skipped, and so are base forms that need an affix or are only allowed in compounds.
+    pub fn read_hunspell<P: AsRef<Path>>(path: P, base_only: bool) -> Result<Vec<String>> {
+        let path = path.as_ref();
+        let aff_path = affix_path(path)
+            .ok_or_else(|| invalid_data(format!("no affix file for {}", path.display())))?;
+        let encoding = read_encoding(&aff_path)?;
+        let affixes = Affixes::parse(&read_file(&aff_path, &encoding)?)?;
+        let dic = read_file(path, &encoding)?;
+        let mut forbidden = HashSet::new();
+        let mut forms = Vec::new();
+        // The first line contains the approximate number of words.
+        for line in dic.lines().skip(1) {
+            let entry = match line.split_whitespace().next() {
+                Some(entry) if !line.starts_with('\t') => entry,
+                _ => continue,
+            };
+            let (word, flags) = match entry.find('/') {
+                Some(i) if i > 0 => (&entry[..i], affixes.flag_type.parse(&entry[(i + 1)..])),
+                _ => (entry, Vec::new()),
+            };
+            let word: Vec<char> = word.chars().collect();
+            if affixes.has(&flags, &affixes.forbidden) {
+                forbidden.insert(word);
+                continue;
+            }
+            if affixes.has(&flags, &affixes.only_in_compound) {
+                continue;
+            }
+            if base_only {
+                if !affixes.has(&flags, &affixes.need_affix) {
+                    forms.push(word);
+                }
+            } else {
+                affixes.expand(&word, &flags, &mut forms);
+            }
+        }
+        let mut seen = HashSet::new();
+        Ok(forms.into_iter()
+               .filter(|form| !forbidden.contains(form) && seen.insert(form.clone()))
+               .map(|form| form.into_iter().collect())
+               .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dict::Dict;
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::Write;
+
+    const AFF: &str = "SET UTF-8
+FORBIDDENWORD !
+NEEDAFFIX ?
+ONLYINCOMPOUND C
+
+SFX S Y 2
+SFX S 0 s [^y]
+SFX S y ies [^aeiou]y
+
+PFX U Y 1
+PFX U 0 un .
+
+SFX V N 1
+SFX V 0 er/S .
+";
+
+    const DIC: &str = "7
+fly/S
+do/U
+tie/SU
+teach/V
+kitten/!
+sharp/?S
+ies/C
+ties/!
+";
+
+    #[test]
+    fn test_read_hunspell() {
+        let dir = env::temp_dir();
+        let name = format!("crosswords-rs-test-{}", ::std::process::id());
+        let dic_path = dir.join(format!("{}.dic", name));
+        let aff_path = dir.join(format!("{}.aff", name));
+        File::create(&dic_path).unwrap().write_all(DIC.as_bytes()).unwrap();
+        File::create(&aff_path).unwrap().write_all(AFF.as_bytes()).unwrap();
+        let words = Dict::read_hunspell(&dic_path, false).unwrap();
+        let base_words = Dict::read_hunspell(&dic_path, true).unwrap();
+        fs::remove_file(&dic_path).unwrap();
+        fs::remove_file(&aff_path).unwrap();
+        assert_eq!(vec!["fly", "flies", "do", "undo", "tie", "untie", "unties", "teach",
+                        "teacher", "teachers", "sharps"],
+                   words);
+        assert_eq!(vec!["fly", "do", "tie", "teach"], base_words);
+    }
+}
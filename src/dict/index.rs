@@ -0,0 +1,674 @@
+//! The index of a dictionary: the sorted lists of the indices of the words satisfying each
+//! `WordConstraint`, and of the words consisting of each multiset of letters. It is either built in
+//! memory, or memory-mapped from a binary index file, so that loading a precompiled dictionary
+//! doesn't need to copy or rebuild the lists. The lengths of the constraint lists are exactly the
+//! frequencies counted by `WordStats`, which uses them directly instead of copying them.
+//!
+//! All numbers in the file are stored as 32 bit little endian integers, and characters as their
+//! code points:
+//!
+//! * The header: `MAGIC`, `VERSION`, the maximum n-gram length, the maximum gap between the
+//!   letters of an indexed pair, the length and characters of the language code, the number of
+//!   words, the number of constraint lists, the total length of the constraint lists, the number
+//!   of anagram lists and the length of the anagram section.
+//! * For each word: its score, its length and its characters, followed by the length and
+//!   characters of its display form, or 0 if it has none.
+//! * The constraint table, sorted by key: For each constraint, its key - the word length, the
+//!   constraint's type (0 for `Length`, 1 for n-grams, 2 for pairs of letters), the position, the
+//!   second position (only nonzero for pairs) and the n-gram's or pair's characters, padded with
+//!   zeros to `key_width` characters - followed by the start of its list and the list's length.
+//! * The word indices of all constraint lists, concatenated.
+//! * The start of each entry in the anagram section, followed by the anagram section itself: For
+//!   each multiset of letters, in ascending order, the number and the sorted letters, followed by
+//!   the list's length and the word indices.
+
+use dict::{Dict, hash_map_memory_usage, vec_memory_usage};
+use dict::word::WordList;
+use memmap::Mmap;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::char;
+use std::cmp::{self, Ordering};
+use std::collections::{HashMap, hash_map};
+use std::fs::File;
+use std::io::{Error, ErrorKind, Result, Write};
+use std::iter;
+use std::mem;
+use std::ops::Range;
+use std::path::Path;
+use std::slice;
+use word_constraint::WordConstraint;
+
+/// The first bytes of every dictionary index file.
+pub const MAGIC: &[u8; 8] = b"CWINDEX\0";
+/// The version of the file format. Files with a different version are rejected.
+pub const VERSION: u32 = 1;
+
+/// The number of values in a constraint's key before its characters.
+const KEY_PREFIX_LEN: usize = 4;
+
+fn invalid_data(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// Returns the number of characters in the constraint keys of an index with n-grams of up to
+/// `max_n` letters: enough for the n-grams and for pairs of letters.
+fn key_width(max_n: usize) -> usize {
+    cmp::max(max_n, 2)
+}
+
+/// Returns the values that identify the constraint in the constraint table.
+fn constraint_key(wc: &WordConstraint, width: usize) -> impl Iterator<Item = u32> + '_ {
+    let (kind, pos, pos1, len, chars) = match *wc {
+        WordConstraint::Length(len) => (0, 0, 0, len, &[][..]),
+        WordConstraint::CharAt(ref c, pos, len) => (1, pos, 0, len, slice::from_ref(c)),
+        WordConstraint::BigramAt(ref ngram, pos, len) => (1, pos, 0, len, &ngram[..]),
+        WordConstraint::TrigramAt(ref ngram, pos, len) => (1, pos, 0, len, &ngram[..]),
+        WordConstraint::NGramAt(ref ngram, pos, len) => (1, pos, 0, len, &ngram[..]),
+        WordConstraint::PairAt(ref pair, pos, pos1, len) => (2, pos, pos1, len, &pair[..]),
+    };
+    iter::once(len as u32)
+        .chain(iter::once(kind))
+        .chain(iter::once(pos as u32))
+        .chain(iter::once(pos1 as u32))
+        .chain(chars.iter().map(|&c| c as u32))
+        .chain(iter::repeat(0))
+        .take(KEY_PREFIX_LEN + width)
+}
+
+/// Returns the characters of a key or of an anagram entry, or `None` if one is invalid.
+fn decode_chars(values: &[u32]) -> Option<Vec<char>> {
+    values.iter().map(|&c| char::from_u32(c)).collect()
+}
+
+/// Returns the constraint with the given key, or `None` if the key is invalid.
+fn decode_constraint(key: &[u32]) -> Option<WordConstraint> {
+    let (len, kind, pos, pos1) = (key[0] as usize, key[1], key[2] as usize, key[3] as usize);
+    let ngram_len = key[KEY_PREFIX_LEN..].iter().take_while(|&&c| c != 0).count();
+    let ngram = decode_chars(&key[KEY_PREFIX_LEN..(KEY_PREFIX_LEN + ngram_len)])?;
+    match kind {
+        0 if ngram.is_empty() => Some(WordConstraint::Length(len)),
+        1 if !ngram.is_empty() => Some(WordConstraint::with_ngram(&ngram, pos, len)),
+        2 if ngram.len() == 2 => {
+            Some(WordConstraint::PairAt([ngram[0], ngram[1]], pos, pos1, len))
+        }
+        _ => None,
+    }
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: u32) -> Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_u32s<W: Write>(writer: &mut W, values: &[u32]) -> Result<()> {
+    for &value in values {
+        write_u32(writer, value)?;
+    }
+    Ok(())
+}
+
+fn write_chars<W, I>(writer: &mut W, chars: I) -> Result<()>
+    where W: Write,
+          I: ExactSizeIterator<Item = char>
+{
+    write_u32(writer, chars.len() as u32)?;
+    for c in chars {
+        write_u32(writer, c as u32)?;
+    }
+    Ok(())
+}
+
+fn write_str<W: Write>(writer: &mut W, s: &str) -> Result<()> {
+    write_u32(writer, s.chars().count() as u32)?;
+    for c in s.chars() {
+        write_u32(writer, c as u32)?;
+    }
+    Ok(())
+}
+
+/// Returns whether the list contains indices of the `word_count` words in strictly ascending
+/// order.
+fn is_valid_list(list: &[u32], word_count: usize) -> bool {
+    let in_bounds = match list.last() {
+        Some(&i) => (i as usize) < word_count,
+        None => true,
+    };
+    in_bounds && list.windows(2).all(|w| w[0] < w[1])
+}
+
+/// Returns the sorted letters and the list of the anagram entry at the given position in the
+/// anagram section, or `None` if it exceeds the section's bounds.
+fn checked_anagram_entry(anagrams: &[u32], start: usize) -> Option<(&[u32], &[u32])> {
+    let entry = anagrams.get(start..)?;
+    let key_len = *entry.first()? as usize;
+    let list_len = *entry.get(key_len.checked_add(1)?)? as usize;
+    let list = entry.get((key_len + 2)..(key_len + 2).checked_add(list_len)?)?;
+    Some((&entry[1..(key_len + 1)], list))
+}
+
+/// Reads numbers and characters from a byte slice.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn u32(&mut self) -> Result<u32> {
+        let end = self.pos + 4;
+        if end > self.bytes.len() {
+            return Err(invalid_data("unexpected end of dictionary index"));
+        }
+        let mut buf = [0; 4];
+        buf.copy_from_slice(&self.bytes[self.pos..end]);
+        self.pos = end;
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn usize(&mut self) -> Result<usize> {
+        self.u32().map(|value| value as usize)
+    }
+
+    fn chars(&mut self) -> Result<Vec<char>> {
+        let len = self.usize()?;
+        (0..len)
+            .map(|_| {
+                     self.u32()
+                         .and_then(|c| char::from_u32(c).ok_or_else(|| invalid_data("bad char")))
+                 })
+            .collect()
+    }
+
+    /// Returns the range of the next `len` values, and skips them.
+    fn section(&mut self, len: usize) -> Result<Range<usize>> {
+        let start = self.pos / 4;
+        let end = len.checked_mul(4)
+            .and_then(|bytes| bytes.checked_add(self.pos))
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| invalid_data("unexpected end of dictionary index"))?;
+        self.pos = end;
+        Ok(start..(start + len))
+    }
+}
+
+/// The lists of a dictionary index that is stored in a memory-mapped file. All keys and lists are
+/// checked when the file is opened, but they are not copied.
+pub struct MappedIndex {
+    mmap: Mmap,
+    /// The number of characters in each constraint key.
+    width: usize,
+    /// The positions of the sections in the file, in units of 4 bytes.
+    table: Range<usize>,
+    postings: Range<usize>,
+    anagram_starts: Range<usize>,
+    anagrams: Range<usize>,
+}
+
+impl MappedIndex {
+    /// Returns the values in the given section of the file.
+    fn section(&self, section: &Range<usize>) -> &[u32] {
+        // The file was checked to be aligned and the sections to be within its bounds, and the
+        // values were written in little endian byte order, which `read_index` requires.
+        let values =
+            unsafe { slice::from_raw_parts(self.mmap.as_ptr() as *const u32, self.mmap.len() / 4) };
+        &values[section.clone()]
+    }
+
+    fn key_len(&self) -> usize {
+        KEY_PREFIX_LEN + self.width
+    }
+
+    /// Returns the constraint table's entry with the given index: its key, and its list.
+    fn record(&self, i: usize) -> (&[u32], &[u32]) {
+        let record_len = self.key_len() + 2;
+        let record = &self.section(&self.table)[(i * record_len)..((i + 1) * record_len)];
+        let (start, len) = (record[self.key_len()] as usize, record[self.key_len() + 1] as usize);
+        (&record[..self.key_len()], &self.section(&self.postings)[start..(start + len)])
+    }
+
+    fn list_count(&self) -> usize {
+        (self.table.end - self.table.start) / (self.key_len() + 2)
+    }
+
+    /// Returns the anagram entry with the given index: its sorted letters, and its list.
+    fn anagram_entry(&self, i: usize) -> (&[u32], &[u32]) {
+        let entry = &self.section(&self.anagrams)[self.section(&self.anagram_starts)[i] as usize..];
+        let key_len = entry[0] as usize;
+        let list_len = entry[key_len + 1] as usize;
+        (&entry[1..(key_len + 1)], &entry[(key_len + 2)..(key_len + 2 + list_len)])
+    }
+
+    fn anagram_count(&self) -> usize {
+        self.anagram_starts.end - self.anagram_starts.start
+    }
+
+    /// Checks that all keys are valid and in ascending order, and that all lists are within the
+    /// bounds of their sections and contain indices of the `word_count` words in ascending order,
+    /// so that lookups in a damaged file can't fail later.
+    fn check(&self, word_count: usize) -> Result<()> {
+        let key_len = self.key_len();
+        let postings = self.section(&self.postings);
+        let mut prev_key = None;
+        for record in self.section(&self.table).chunks(key_len + 2) {
+            let key = &record[..key_len];
+            let (start, len) = (record[key_len] as usize, record[key_len + 1] as usize);
+            let list = start.checked_add(len).and_then(|end| postings.get(start..end));
+            let valid = match list {
+                Some(list) => {
+                    decode_constraint(key).is_some() && prev_key < Some(key) &&
+                    is_valid_list(list, word_count)
+                }
+                None => false,
+            };
+            if !valid {
+                return Err(invalid_data("invalid word constraint"));
+            }
+            prev_key = Some(key);
+        }
+        let anagrams = self.section(&self.anagrams);
+        let mut prev_key = None;
+        for &start in self.section(&self.anagram_starts) {
+            let entry = checked_anagram_entry(anagrams, start as usize);
+            let valid = match entry {
+                Some((key, list)) => {
+                    decode_chars(key).is_some() && key.windows(2).all(|w| w[0] <= w[1]) &&
+                    prev_key < Some(key) && is_valid_list(list, word_count)
+                }
+                None => false,
+            };
+            if !valid {
+                return Err(invalid_data("invalid anagram list"));
+            }
+            prev_key = entry.map(|(key, _)| key);
+        }
+        Ok(())
+    }
+
+    fn get_list(&self, wc: &WordConstraint) -> &[u32] {
+        let (mut low, mut high) = (0, self.list_count());
+        while low < high {
+            let mid = (low + high) / 2;
+            let (key, list) = self.record(mid);
+            match key.iter().cloned().cmp(constraint_key(wc, self.width)) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return list,
+            }
+        }
+        &[]
+    }
+
+    fn get_anagram_list(&self, key: &[char]) -> &[u32] {
+        let (mut low, mut high) = (0, self.anagram_count());
+        while low < high {
+            let mid = (low + high) / 2;
+            let (entry_key, list) = self.anagram_entry(mid);
+            match entry_key.iter().cloned().cmp(key.iter().map(|&c| c as u32)) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return list,
+            }
+        }
+        &[]
+    }
+}
+
+/// The lists of word indices of a dictionary, sorted in ascending order.
+pub enum Index {
+    /// Lists that were built in memory.
+    Built {
+        /// The words satisfying each constraint.
+        lists: HashMap<WordConstraint, Vec<u32>>,
+        /// The words with each multiset of letters, given in sorted order.
+        anagrams: HashMap<Vec<char>, Vec<u32>>,
+    },
+    /// Lists in a memory-mapped index file.
+    Mapped(MappedIndex),
+}
+
+impl Index {
+    /// Returns the list of the words satisfying the constraint.
+    pub fn get_list(&self, wc: &WordConstraint) -> &[u32] {
+        match *self {
+            Index::Built { ref lists, .. } => lists.get(wc).map_or(&[], |list| &list[..]),
+            Index::Mapped(ref index) => index.get_list(wc),
+        }
+    }
+
+    /// Returns the list of the words consisting of exactly the given sorted letters.
+    pub fn get_anagram_list(&self, key: &[char]) -> &[u32] {
+        match *self {
+            Index::Built { ref anagrams, .. } => anagrams.get(key).map_or(&[], |list| &list[..]),
+            Index::Mapped(ref index) => index.get_anagram_list(key),
+        }
+    }
+
+    /// Returns the number of indexed constraints.
+    pub fn list_count(&self) -> usize {
+        match *self {
+            Index::Built { ref lists, .. } => lists.len(),
+            Index::Mapped(ref index) => index.list_count(),
+        }
+    }
+
+    /// Returns an iterator over all indexed constraints and their lists.
+    pub fn lists(&self) -> Lists<'_> {
+        match *self {
+            Index::Built { ref lists, .. } => Lists::Built(lists.iter()),
+            Index::Mapped(ref index) => Lists::Mapped(index, 0..index.list_count()),
+        }
+    }
+
+    /// Returns an iterator over all multisets of letters and the lists of their anagrams.
+    pub fn anagram_lists(&self) -> AnagramLists<'_> {
+        match *self {
+            Index::Built { ref anagrams, .. } => AnagramLists::Built(anagrams.iter()),
+            Index::Mapped(ref index) => AnagramLists::Mapped(index, 0..index.anagram_count()),
+        }
+    }
+
+    /// Returns an estimate of the number of bytes occupied by the lists, or of the size of the
+    /// file if they are memory-mapped.
+    pub fn memory_usage(&self) -> usize {
+        match *self {
+            Index::Built { ref lists, ref anagrams } => {
+                let lists_size: usize = lists.iter()
+                    .map(|(wc, list)| {
+                        let ngram_size = match *wc {
+                            WordConstraint::NGramAt(ref ngram, _, _) => vec_memory_usage(ngram),
+                            _ => 0,
+                        };
+                        ngram_size + vec_memory_usage(list)
+                    })
+                    .sum();
+                let anagrams_size: usize = anagrams.iter()
+                    .map(|(key, list)| vec_memory_usage(key) + vec_memory_usage(list))
+                    .sum();
+                hash_map_memory_usage(lists) + lists_size + hash_map_memory_usage(anagrams) +
+                anagrams_size
+            }
+            Index::Mapped(ref index) => index.mmap.len(),
+        }
+    }
+}
+
+/// An iterator over the constraints of an `Index` and their lists.
+pub enum Lists<'a> {
+    Built(hash_map::Iter<'a, WordConstraint, Vec<u32>>),
+    Mapped(&'a MappedIndex, Range<usize>),
+}
+
+impl<'a> Iterator for Lists<'a> {
+    type Item = (Cow<'a, WordConstraint>, &'a [u32]);
+
+    fn next(&mut self) -> Option<(Cow<'a, WordConstraint>, &'a [u32])> {
+        match *self {
+            Lists::Built(ref mut iter) => {
+                iter.next().map(|(wc, list)| (Cow::Borrowed(wc), &list[..]))
+            }
+            Lists::Mapped(index, ref mut range) => {
+                range.next().map(|i| {
+                    let (key, list) = index.record(i);
+                    // The keys were checked when the file was opened.
+                    (Cow::Owned(decode_constraint(key).unwrap()), list)
+                })
+            }
+        }
+    }
+}
+
+/// An iterator over the multisets of letters of an `Index`, in sorted order, and their lists.
+pub enum AnagramLists<'a> {
+    Built(hash_map::Iter<'a, Vec<char>, Vec<u32>>),
+    Mapped(&'a MappedIndex, Range<usize>),
+}
+
+impl<'a> Iterator for AnagramLists<'a> {
+    type Item = (Cow<'a, [char]>, &'a [u32]);
+
+    fn next(&mut self) -> Option<(Cow<'a, [char]>, &'a [u32])> {
+        match *self {
+            AnagramLists::Built(ref mut iter) => {
+                iter.next().map(|(key, list)| (Cow::Borrowed(&key[..]), &list[..]))
+            }
+            AnagramLists::Mapped(index, ref mut range) => {
+                range.next().map(|i| {
+                    let (key, list) = index.anagram_entry(i);
+                    (Cow::Owned(decode_chars(key).unwrap_or_default()), list)
+                })
+            }
+        }
+    }
+}
+
+impl Dict {
+    /// Writes the dictionary in the binary index format, which can be read with `read_index`. The
+    /// lists are written in a fixed order, so the same dictionary always results in the same file.
+    pub fn write_index<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let width = key_width(self.max_n);
+        let mut lists: Vec<(Vec<u32>, &[u32])> = self.index
+            .lists()
+            .map(|(wc, list)| (constraint_key(&wc, width).collect(), list))
+            .collect();
+        lists.sort();
+        let mut anagrams: Vec<(Cow<[char]>, &[u32])> = self.index.anagram_lists().collect();
+        anagrams.sort();
+        writer.write_all(MAGIC)?;
+        write_u32(writer, VERSION)?;
+        write_u32(writer, self.max_n as u32)?;
+        write_u32(writer, self.max_gap as u32)?;
+        write_str(writer, self.lang.code())?;
+        write_u32(writer, self.words.len() as u32)?;
+        write_u32(writer, lists.len() as u32)?;
+        write_u32(writer, lists.iter().map(|&(_, list)| list.len()).sum::<usize>() as u32)?;
+        write_u32(writer, anagrams.len() as u32)?;
+        write_u32(writer,
+                  anagrams.iter().map(|&(ref key, list)| key.len() + list.len() + 2).sum::<usize>()
+                      as u32)?;
+        for (word, score, display) in self.all_entries() {
+            write_u32(writer, score as u32)?;
+            write_chars(writer, word.chars())?;
+            write_str(writer, display.unwrap_or(""))?;
+        }
+        let mut start = 0;
+        for &(ref key, list) in &lists {
+            write_u32s(writer, key)?;
+            write_u32(writer, start as u32)?;
+            write_u32(writer, list.len() as u32)?;
+            start += list.len();
+        }
+        for &(_, list) in &lists {
+            write_u32s(writer, list)?;
+        }
+        let mut start = 0;
+        for &(ref key, list) in &anagrams {
+            write_u32(writer, start as u32)?;
+            start += key.len() + list.len() + 2;
+        }
+        for &(ref key, list) in &anagrams {
+            write_chars(writer, key.iter().cloned())?;
+            write_u32(writer, list.len() as u32)?;
+            write_u32s(writer, list)?;
+        }
+        Ok(())
+    }
+
+    /// Returns whether the file at the given path starts with the index file's `MAGIC` bytes.
+    pub fn is_index_file<P: AsRef<Path>>(path: P) -> bool {
+        use std::io::Read;
+        let mut buf = [0; 8];
+        File::open(path)
+            .and_then(|mut file| file.read_exact(&mut buf))
+            .map(|_| &buf == MAGIC)
+            .unwrap_or(false)
+    }
+
+    /// Reads a dictionary from a binary index file, written by `write_index`. The file is mapped
+    /// into memory, and only the words are copied from it: The lists are used where they are.
+    pub fn read_index<P: AsRef<Path>>(path: P) -> Result<Dict> {
+        if cfg!(target_endian = "big") {
+            return Err(invalid_data("index files can only be read on little endian platforms"));
+        }
+        let file = File::open(path)?;
+        let mmap = unsafe { Mmap::map(&file)? };
+        if mmap.len() < MAGIC.len() || &mmap[..MAGIC.len()] != MAGIC {
+            return Err(invalid_data("not a dictionary index file"));
+        }
+        let align = mem::align_of::<u32>();
+        if mmap.len() & (align - 1) != 0 || mmap.as_ptr() as usize & (align - 1) != 0 {
+            return Err(invalid_data("misaligned dictionary index"));
+        }
+        let mut reader = Reader {
+            bytes: &mmap,
+            pos: MAGIC.len(),
+        };
+        if reader.u32()? != VERSION {
+            return Err(invalid_data("unsupported dictionary index version"));
+        }
+        let max_n = reader.usize()?;
+        let max_gap = reader.usize()?;
+        let lang = reader.chars()?
+            .into_iter()
+            .collect::<String>()
+            .parse()
+            .map_err(|err: String| invalid_data(&err))?;
+        let word_count = reader.usize()?;
+        let list_count = reader.usize()?;
+        let postings_len = reader.usize()?;
+        let anagram_count = reader.usize()?;
+        let anagrams_len = reader.usize()?;
+        let mut words = WordList::new();
+        let mut scores = Vec::with_capacity(word_count);
+        let mut display_forms = HashMap::new();
+        for i in 0..word_count {
+            scores.push(reader.u32()? as i32);
+            words.push(&reader.chars()?);
+            let display = reader.chars()?;
+            if !display.is_empty() {
+                display_forms.insert(i, display.into_iter().collect());
+            }
+        }
+        let width = key_width(max_n);
+        let table = reader.section(list_count * (KEY_PREFIX_LEN + width + 2))?;
+        let postings = reader.section(postings_len)?;
+        let anagram_starts = reader.section(anagram_count)?;
+        let anagrams = reader.section(anagrams_len)?;
+        let index = MappedIndex {
+            mmap,
+            width,
+            table,
+            postings,
+            anagram_starts,
+            anagrams,
+        };
+        index.check(word_count)?;
+        // The table is sorted by word length first, and there is a `Length` constraint for each.
+        let min_len = if list_count == 0 {
+            usize::MAX
+        } else {
+            index.record(0).0[0] as usize
+        };
+        Ok(Dict {
+               words,
+               scores,
+               index: Index::Mapped(index),
+               max_n,
+               max_gap,
+               min_len,
+               lookups: Cell::new(0),
+               matches: Cell::new(0),
+               display_forms,
+               lang,
+           })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dict::{Dict, Lang, Word};
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::{ErrorKind, Read};
+    use test_util::*;
+
+    #[test]
+    fn test_index() {
+        let entries = vec![(str_to_cvec("FOO"), 10, None),
+                           (str_to_cvec("FOE"), -5, Some("Foe!".to_owned())),
+                           (str_to_cvec("TOE"), 30, None),
+                           (str_to_cvec("OE"), 20, None)];
+        let dict = Dict::from_entries(entries, 3, 2, Lang::French);
+        let path = env::temp_dir().join(format!("crosswords-rs-test-{}.idx", ::std::process::id()));
+        dict.write_index(&mut File::create(&path).unwrap()).unwrap();
+        assert!(Dict::is_index_file(&path));
+        let read_dict = Dict::read_index(&path).unwrap();
+        let mut bytes = Vec::new();
+        File::open(&path).unwrap().read_to_end(&mut bytes).unwrap();
+        fs::remove_file(&path).unwrap();
+        for pattern in &["#OE", "F#E", "F##", "##", "####"] {
+            let pattern = str_to_cvec(pattern);
+            assert_eq!(dict.matching_words(&pattern).collect::<Vec<_>>(),
+                       read_dict.matching_words(&pattern).collect::<Vec<_>>());
+        }
+        let anagrams = |dict: &Dict, letters, sub| {
+            dict.anagrams(&str_to_cvec(letters), sub).map(Word::to_vec).collect::<Vec<_>>()
+        };
+        assert_eq!(anagrams(&dict, "OEF", false), anagrams(&read_dict, "OEF", false));
+        assert_eq!(anagrams(&dict, "OT#", true), anagrams(&read_dict, "OT#", true));
+        assert_eq!(Some(-5), read_dict.get_score(&str_to_cvec("FOE")));
+        assert_eq!(Some("Foe!"), read_dict.get_display_form(&str_to_cvec("FOE")));
+        assert_eq!(Lang::French, read_dict.get_lang());
+        assert_eq!(2, read_dict.get_min_len());
+        assert_eq!(2, read_dict.get_max_gap());
+        assert_eq!(dict.list_count(), read_dict.list_count());
+        // Writing the dictionary again results in the same file.
+        let mut written = Vec::new();
+        read_dict.write_index(&mut written).unwrap();
+        assert_eq!(bytes, written);
+        let mut bytes = Vec::new();
+        dict.write_index(&mut bytes).unwrap();
+        assert_eq!(written, bytes);
+    }
+
+    #[test]
+    fn test_invalid_index() {
+        let dict = Dict::from_entries(vec![(str_to_cvec("AB"), 10, None),
+                                           (str_to_cvec("BA"), 20, None)],
+                                      1,
+                                      0,
+                                      Lang::English);
+        let mut bytes = Vec::new();
+        dict.write_index(&mut bytes).unwrap();
+        let get = |bytes: &[u8], i: usize| {
+            let mut buf = [0; 4];
+            buf.copy_from_slice(&bytes[(4 * i)..(4 * i + 4)]);
+            u32::from_le_bytes(buf) as usize
+        };
+        // The header: two values of magic bytes, the version, max_n, max_gap, three values for the
+        // language code "en", and then the numbers of words, lists and postings, etc.
+        let (word_count, postings_len) = (get(&bytes, 8), get(&bytes, 10));
+        assert_eq!(2, word_count);
+        let anagrams_len = get(&bytes, 11) + get(&bytes, 12);
+        let postings_start = bytes.len() / 4 - anagrams_len - postings_len;
+        let path = env::temp_dir()
+            .join(format!("crosswords-rs-test-invalid-{}.idx", ::std::process::id()));
+        let read = |bytes: &[u8]| {
+            fs::write(&path, bytes).unwrap();
+            let result = Dict::read_index(&path).map(|_| ());
+            fs::remove_file(&path).unwrap();
+            result.map_err(|err| err.kind())
+        };
+        assert_eq!(Ok(()), read(&bytes));
+        // A word index that is out of bounds.
+        let mut damaged = bytes.clone();
+        damaged[(4 * postings_start)..(4 * postings_start + 4)]
+            .copy_from_slice(&(word_count as u32).to_le_bytes());
+        assert_eq!(Err(ErrorKind::InvalidData), read(&damaged));
+        // Two word indices in the wrong order: The `Length` constraint comes first and lists both.
+        let mut damaged = bytes.clone();
+        damaged[(4 * postings_start)..(4 * postings_start + 8)]
+            .copy_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(Err(ErrorKind::InvalidData), read(&damaged));
+        // A truncated file.
+        assert_eq!(Err(ErrorKind::InvalidData), read(&bytes[..(bytes.len() - 4)]));
+    }
+}
@@ -0,0 +1,254 @@
+use std::str::FromStr;
+
+/// Accented Latin letters that are folded to their base letters by default.
+const LATIN_FOLDINGS: &[(char, &str)] = &[('À', "A"), ('Á', "A"), ('Â', "A"), ('Ã', "A"),
+                                          ('Ä', "A"), ('Å', "A"), ('Æ', "AE"), ('Ç', "C"),
+                                          ('È', "E"), ('É', "E"), ('Ê', "E"), ('Ë', "E"),
+                                          ('Ì', "I"), ('Í', "I"), ('Î', "I"), ('Ï', "I"),
+                                          ('Ñ', "N"), ('Ò', "O"), ('Ó', "O"), ('Ô', "O"),
+                                          ('Õ', "O"), ('Ö', "O"), ('Ø', "O"), ('Œ', "OE"),
+                                          ('Ù', "U"), ('Ú', "U"), ('Û', "U"), ('Ü', "U"),
+                                          ('Ý', "Y"), ('Ÿ', "Y")];
+
+const LATIN_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// A language profile: It defines the alphabet of the puzzle, and how words from a word list are
+/// normalized to it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Lang {
+    /// Letters A to Z; accents are removed, and apostrophes, hyphens, dots and spaces are
+    /// stripped.
+    English,
+    /// Letters A to Z; umlauts are replaced by the corresponding diphthongs, e. g. `Ä` by `AE`,
+    /// `ß` by `SS`, and other accents are removed. Hyphens and spaces are stripped. This is the
+    /// default.
+    #[default]
+    German,
+    /// Letters A to Z; accents are removed, ligatures are split, and apostrophes, hyphens and
+    /// spaces are stripped.
+    French,
+    /// Letters A to Z and `Ñ`; accents are removed, and hyphens and spaces are stripped.
+    Spanish,
+    /// Letters A to Z, `Å`, `Ä` and `Ö`; `Æ` and `Ø` count as `Ä` and `Ö`.
+    Swedish,
+    /// Letters A to Z, `Æ`, `Ø` and `Å`; `Ä` and `Ö` count as `Æ` and `Ø`. This is also used for
+    /// Norwegian.
+    Danish,
+    /// The Turkish alphabet, with dotted and dotless `I` as separate letters: `i` is capitalized
+    /// as `İ` and `ı` as `I`. Apostrophes, hyphens and spaces are stripped.
+    Turkish,
+    /// The 24 letters of the Greek alphabet; accents and diaereses are removed, and the final
+    /// sigma `ς` counts as `Σ`.
+    Greek,
+}
+
+impl FromStr for Lang {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Lang, String> {
+        match s {
+            "en" => Ok(Lang::English),
+            "de" => Ok(Lang::German),
+            "fr" => Ok(Lang::French),
+            "es" => Ok(Lang::Spanish),
+            "sv" => Ok(Lang::Swedish),
+            "da" | "no" => Ok(Lang::Danish),
+            "tr" => Ok(Lang::Turkish),
+            "el" => Ok(Lang::Greek),
+            _ => Err(format!("unknown language: {}", s)),
+        }
+    }
+}
+
+impl Lang {
+    /// Returns the language code that is parsed as this language.
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::English => "en",
+            Lang::German => "de",
+            Lang::French => "fr",
+            Lang::Spanish => "es",
+            Lang::Swedish => "sv",
+            Lang::Danish => "da",
+            Lang::Turkish => "tr",
+            Lang::Greek => "el",
+        }
+    }
+
+    /// Returns the letters that can appear in the puzzle.
+    pub fn alphabet(self) -> &'static str {
+        match self {
+            Lang::English | Lang::German | Lang::French => LATIN_ALPHABET,
+            Lang::Spanish => "ABCDEFGHIJKLMNÑOPQRSTUVWXYZ",
+            Lang::Swedish => "ABCDEFGHIJKLMNOPQRSTUVWXYZÅÄÖ",
+            Lang::Danish => "ABCDEFGHIJKLMNOPQRSTUVWXYZÆØÅ",
+            Lang::Turkish => "ABCÇDEFGĞHIİJKLMNOÖPRSŞTUÜVYZ",
+            Lang::Greek => "ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩ",
+        }
+    }
+
+    /// Returns the replacements for capital letters that are not part of the alphabet. They take
+    /// precedence over the `LATIN_FOLDINGS`, which apply to all languages except Greek.
+    fn foldings(self) -> &'static [(char, &'static str)] {
+        match self {
+            Lang::English | Lang::French | Lang::Spanish => &[],
+            Lang::German => &[('Ä', "AE"), ('Ö', "OE"), ('Ü', "UE"), ('ẞ', "SS")],
+            Lang::Swedish => &[('Æ', "Ä"), ('Ø', "Ö")],
+            Lang::Danish => &[('Ä', "Æ"), ('Ö', "Ø")],
+            Lang::Turkish => &[('Î', "İ")],
+            Lang::Greek => {
+                &[('Ά', "Α"), ('Έ', "Ε"), ('Ή', "Η"), ('Ί', "Ι"), ('Ϊ', "Ι"), ('Ό', "Ο"),
+                  ('Ύ', "Υ"), ('Ϋ', "Υ"), ('Ώ', "Ω")]
+            }
+        }
+    }
+
+    /// Returns the characters that are removed from words, e. g. punctuation.
+    fn stripped(self) -> &'static str {
+        match self {
+            Lang::English => "'-. ",
+            Lang::French | Lang::Turkish => "'- ",
+            Lang::German | Lang::Spanish | Lang::Swedish | Lang::Danish => "- ",
+            // Combining acute accent and diaeresis, which can result from capitalization.
+            Lang::Greek => "- \u{301}\u{308}",
+        }
+    }
+
+    /// Returns common inflection and derivation suffixes in normalized form, longest first, which
+    /// a simple stemmer can strip to find related words.
+    pub fn suffixes(self) -> &'static [&'static str] {
+        match self {
+            Lang::English => {
+                &["NESSES", "MENTS", "NESS", "MENT", "INGS", "IEST", "ING", "IES", "IER", "IED",
+                  "EST", "ERS", "ER", "ED", "ES", "LY", "S", "E", "Y"]
+            }
+            Lang::German => {
+                &["UNGEN", "HEIT", "KEIT", "UNG", "ERN", "ERS", "END", "EN", "ER", "ES", "EM",
+                  "ST", "E", "N", "T"]
+            }
+            Lang::French => {
+                &["EMENTS", "ATIONS", "EMENT", "ATION", "EUSES", "EUSE", "ANTS", "ANT", "EUX",
+                  "EES", "EE", "ES", "ER", "EZ", "E", "S", "X"]
+            }
+            Lang::Spanish => {
+                &["AMIENTOS", "AMIENTO", "ACIONES", "ACION", "MENTE", "IENDO", "ANDO", "ADAS",
+                  "ADOS", "IDAS", "IDOS", "ADA", "ADO", "IDA", "IDO", "ES", "AS", "OS", "AR",
+                  "ER", "IR", "A", "O", "E", "S"]
+            }
+            Lang::Swedish => {
+                &["ARNAS", "ERNAS", "ORNAS", "ARNA", "ERNA", "ORNA", "ANDE", "ENDE", "ARE", "AST",
+                  "EN", "ER", "AR", "OR", "ET", "AD", "A", "E", "S"]
+            }
+            Lang::Danish => {
+                &["ERNES", "ERNE", "ENES", "ENDE", "EDE", "ENE", "ERE", "EN", "ER", "ET", "ES",
+                  "E", "S"]
+            }
+            Lang::Turkish => {
+                &["LARI", "LERİ", "LAR", "LER", "DAN", "DEN", "DA", "DE", "TA", "TE", "IN", "İN",
+                  "UN", "ÜN", "I", "İ", "U", "Ü"]
+            }
+            Lang::Greek => {
+                &["ΟΥΣ", "ΟΣ", "ΗΣ", "ΑΣ", "ΕΣ", "ΟΥ", "ΩΝ", "ΑΙ", "ΟΙ", "Α", "Η", "Ο", "Ι", "Ε"]
+            }
+        }
+    }
+
+    fn push_upper(self, c: char, result: &mut String) {
+        match (self, c) {
+            (Lang::Turkish, 'i') => result.push('İ'),
+            (Lang::Turkish, 'ı') => result.push('I'),
+            _ => result.extend(c.to_uppercase()),
+        }
+    }
+
+    fn fold(self, c: char) -> Option<&'static str> {
+        if self.alphabet().contains(c) {
+            return None;
+        }
+        let latin = if self == Lang::Greek { &[][..] } else { LATIN_FOLDINGS };
+        self.foldings().iter().chain(latin).find(|&&(f, _)| f == c).map(|&(_, s)| s)
+    }
+
+    /// Returns the enumeration of a phrase, i. e. the number of letters of each of its words in
+    /// the grid, separated by commas for spaces and by hyphens for hyphens, e. g. `(3,4)` for
+    /// "New York" or `(5-5)` for "Rolls-Royce".
+    pub fn enumeration(self, display: &str) -> String {
+        let mut result = "(".to_owned();
+        let mut group = String::new();
+        for c in display.trim().chars().chain(Some(' ')) {
+            let separator = match c {
+                '-' => '-',
+                c if c.is_whitespace() => ',',
+                c => {
+                    group.push(c);
+                    continue;
+                }
+            };
+            if let Some(word) = self.normalize(&group) {
+                result.push_str(&word.len().to_string());
+                result.push(separator);
+            }
+            group.clear();
+        }
+        result.pop();
+        result.push(')');
+        result
+    }
+
+    /// Converts the word to the alphabet: It is capitalized, the language's punctuation is
+    /// removed and letters outside the alphabet are replaced. Returns `None` if the word is empty
+    /// or still contains characters that are not part of the alphabet.
+    pub fn normalize(self, str_word: &str) -> Option<Vec<char>> {
+        let mut upper = String::new();
+        for c in str_word.trim().chars() {
+            self.push_upper(c, &mut upper);
+        }
+        let mut word = String::new();
+        for c in upper.chars().filter(|&c| !self.stripped().contains(c)) {
+            match self.fold(c) {
+                Some(folded) => word.push_str(folded),
+                None => word.push(c),
+            }
+        }
+        if !word.is_empty() && word.chars().all(|c| self.alphabet().contains(c)) {
+            Some(word.chars().collect())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::*;
+
+    #[test]
+    fn test_normalize() {
+        let normalize = |lang: &str, word| lang.parse::<Lang>().unwrap().normalize(word);
+        assert_eq!(Some(str_to_cvec("OEHA")), normalize("de", "Öha"));
+        assert_eq!(Some(str_to_cvec("EMAIL")), normalize("de", "E-Mail"));
+        assert_eq!(None, normalize("de", "Wow!"));
+        assert_eq!(Some(str_to_cvec("EMAIL")), normalize("en", "e-mail"));
+        assert_eq!(Some(str_to_cvec("COEURDELION")), normalize("fr", "Cœur-de-lion"));
+        assert_eq!(Some(str_to_cvec("AQUIENSEÑOR")), normalize("es", "a quién señor"));
+        assert_eq!(Some(str_to_cvec("SMÖRGÅS")), normalize("sv", "smørgås"));
+        assert_eq!(Some(str_to_cvec("SMØRGÅS")), normalize("no", "smörgås"));
+        assert_eq!(Some(str_to_cvec("İSTANBULDA")), normalize("tr", "istanbul'da"));
+        assert_eq!(Some(str_to_cvec("KIZ")), normalize("tr", "kız"));
+        assert_eq!(None, normalize("tr", "Quiz"));
+        assert_eq!(Some(str_to_cvec("ΛΟΓΟΣ")), normalize("el", "λόγος"));
+        assert_eq!(Some(str_to_cvec("ΔΙΑΙΤΑ")), normalize("el", "δίαιτα"));
+        assert_eq!(Some(str_to_cvec("ΠΡΩΤΕΥΟΥΣΑ")), normalize("el", "πρωτεύουσα"));
+        assert!("xx".parse::<Lang>().is_err());
+    }
+
+    #[test]
+    fn test_enumeration() {
+        assert_eq!("(3,4)", Lang::German.enumeration("New York"));
+        assert_eq!("(5-5)", Lang::English.enumeration("Rolls-Royce"));
+        assert_eq!("(12)", Lang::German.enumeration("Düsenjäger"));
+        assert_eq!("(9,1,4)", Lang::English.enumeration("rock'n'roll à gogo"));
+        assert_eq!(Lang::Turkish, Lang::Turkish.code().parse().unwrap());
+    }
+}
@@ -0,0 +1,708 @@
+mod corpus;
+mod hunspell;
+mod index;
+mod lang;
+mod stem;
+mod tag;
+mod variants;
+mod word;
+
+use cw::BLOCK;
+use rand;
+use rand::Rng;
+use std::cmp;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::iter;
+use std::mem;
+use word_constraint::WordConstraint;
+
+pub use dict::corpus::WordCounts;
+pub use dict::lang::Lang;
+pub use dict::stem::Stemmer;
+pub use dict::tag::{Tag, parse_tags};
+pub use dict::variants::VariantGroups;
+pub use dict::word::Word;
+use dict::index::Index;
+use dict::word::{WordList, WordListIter};
+
+/// The score of words from plain word lists, which don't specify a score.
+pub const DEFAULT_SCORE: i32 = 50;
+
+/// An entry of a word list: the normalized word, its score and its original form, if it differs.
+pub type WordEntry = (Vec<char>, i32, Option<String>);
+
+/// The maximum length of the n-grams that are indexed by default.
+pub const DEFAULT_MAX_N: usize = 3;
+
+/// The maximum number of letters between two letters whose pair is indexed by default. Patterns
+/// only use pairs to link the letters on either side of a gap, and most gaps are a single letter.
+pub const DEFAULT_MAX_GAP: usize = 1;
+
+/// Removes all elements less than `i` from the beginning of the sorted list, and returns whether
+/// the next element is `i`. Since this is called with increasing values of `i`, it uses exponential
+/// search, so that advancing by a few elements is cheap.
+fn advance_to(list: &mut &[u32], i: u32) -> bool {
+    let mut bound = 1;
+    while bound < list.len() && list[bound] < i {
+        bound *= 2;
+    }
+    let end = cmp::min(bound + 1, list.len());
+    match list[..end].binary_search(&i) {
+        Ok(pos) => {
+            *list = &list[pos..];
+            true
+        }
+        Err(pos) => {
+            *list = &list[pos..];
+            false
+        }
+    }
+}
+
+/// Returns the elements contained in all of the sorted lists, in ascending order. It is most
+/// efficient if the first list is the shortest one.
+fn intersect(lists: &[&[u32]]) -> Vec<u32> {
+    let mut result = Vec::new();
+    let mut rests = lists[1..].to_vec();
+    for &i in lists[0] {
+        if rests.iter_mut().all(|rest| advance_to(rest, i)) {
+            result.push(i);
+        } else if rests.iter().any(|rest| rest.is_empty()) {
+            break;
+        }
+    }
+    result
+}
+
+/// Returns the number of letters in the sorted `word_key` that are not contained in the sorted
+/// `key`, counting repeated letters separately.
+fn missing_letters(word_key: &[char], key: &[char]) -> usize {
+    let mut key = key.iter().peekable();
+    let mut missing = 0;
+    for c in word_key {
+        while let Some(&k) = key.peek() {
+            if k >= c {
+                break;
+            }
+            key.next();
+        }
+        if key.peek() == Some(&c) {
+            key.next();
+        } else {
+            missing += 1;
+        }
+    }
+    missing
+}
+
+/// An iterator over all words satisfying a given `WordConstraint`, in descending order of their
+/// scores.
+pub struct PatternIter<'a> {
+    dict: &'a Dict,
+    list: Cow<'a, [u32]>,
+    index: usize,
+}
+
+impl<'a> PatternIter<'a> {
+    /// Returns the score of the next word without consuming it.
+    pub fn peek_score(&self) -> Option<i32> {
+        self.list.get(self.index).map(|&i| self.dict.scores[i as usize])
+    }
+}
+
+impl<'a> Iterator for PatternIter<'a> {
+    type Item = Word<'a>;
+
+    fn next(&mut self) -> Option<Word<'a>> {
+        let dict = self.dict;
+        let word = self.list.get(self.index).map(|&i| dict.words.get(i as usize));
+        self.index += 1;
+        word
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.list.len().saturating_sub(self.index);
+        (len, Some(len))
+    }
+}
+
+/// The approximate number of bytes the allocator needs for each heap allocation, in addition to
+/// the requested size.
+const ALLOCATION_OVERHEAD: usize = 16;
+
+/// Returns an estimate of the number of bytes occupied by the vector's heap buffer.
+fn vec_memory_usage<T>(vec: &Vec<T>) -> usize {
+    if vec.capacity() == 0 {
+        0
+    } else {
+        vec.capacity() * mem::size_of::<T>() + ALLOCATION_OVERHEAD
+    }
+}
+
+/// Returns an estimate of the number of bytes occupied by the map's table, not counting the heap
+/// buffers owned by the keys and values. The table has a power of two number of buckets, at most
+/// 7/8 of which are used, and each bucket contains an entry and a control byte.
+fn hash_map_memory_usage<K, V>(map: &HashMap<K, V>) -> usize {
+    if map.capacity() == 0 {
+        return 0;
+    }
+    let buckets = (map.capacity() * 8 / 7).next_power_of_two();
+    buckets * (mem::size_of::<(K, V)>() + 1) + 16 + ALLOCATION_OVERHEAD
+}
+
+/// A `Dict` stores a list of words - in a compact `WordList` - with a score for each word, and
+/// indexes them for efficiently iterating over all words satisfying a given `WordConstraint`.
+pub struct Dict {
+    words: WordList,
+    scores: Vec<i32>,
+    /// The lists of word indices for each constraint and each set of anagrams.
+    index: Index,
+    max_n: usize,
+    /// The maximum number of letters between the two letters of an indexed pair.
+    max_gap: usize,
+    /// The length of the shortest word, or `usize::MAX` if there are no words.
+    min_len: usize,
+    lookups: Cell<usize>,
+    matches: Cell<usize>,
+    /// The original forms of the words whose normalization was lossy, e. g. "New York".
+    display_forms: HashMap<usize, String>,
+    /// The language whose profile was used to normalize the words.
+    lang: Lang,
+}
+
+impl Dict {
+    /// Create a new `Dict` from the given word list entries, as returned by `parse_line` for the
+    /// given language: the words, their scores and their display forms, if they differ from the
+    /// words. N-grams of up to `max_n` letters are indexed, and pairs of letters with up to
+    /// `max_gap` letters between them. Words with equal scores are shuffled.
+    pub fn from_entries<T>(entries: T, max_n: usize, max_gap: usize, lang: Lang) -> Dict
+        where T: IntoIterator<Item = (Vec<char>, i32, Option<String>)>
+    {
+        Dict::from_entries_with_rng(entries, max_n, max_gap, lang, &mut rand::thread_rng())
+    }
+
+    /// Create a new `Dict` like `from_entries`, but use the given random number generator to
+    /// shuffle words with equal scores. With a deterministic generator, the same entries always
+    /// result in the same dictionary.
+    pub fn from_entries_with_rng<T, R>(entries: T,
+                                       max_n: usize,
+                                       max_gap: usize,
+                                       lang: Lang,
+                                       rng: &mut R)
+                                       -> Dict
+        where T: IntoIterator<Item = (Vec<char>, i32, Option<String>)>,
+              R: Rng
+    {
+        if max_n == 0 {
+            panic!("max_n must be at least 1");
+        }
+        let mut existing_words = HashSet::new();
+        let mut entries: Vec<(Vec<char>, i32, Option<String>)> = entries
+            .into_iter()
+            .filter(|(w, _, _)| existing_words.insert(w.clone()))
+            .collect();
+        rng.shuffle(&mut entries[..]);
+        // The sort is stable, so words with equal scores remain shuffled.
+        entries.sort_by_key(|&(_, score, _)| -score);
+        let words: WordList = entries.iter().map(|(word, _, _)| &word[..]).collect();
+        let scores = entries.iter().map(|&(_, score, _)| score).collect();
+        let min_len = entries.iter().map(|(word, _, _)| word.len()).min().unwrap_or(usize::MAX);
+        let mut display_forms = HashMap::new();
+        let mut lists: HashMap<WordConstraint, Vec<u32>> = HashMap::new();
+        let mut anagrams: HashMap<Vec<char>, Vec<u32>> = HashMap::new();
+        for (i, (word, _, display)) in entries.into_iter().enumerate() {
+            if let Some(display) = display {
+                display_forms.insert(i, display);
+            }
+            for woco in WordConstraint::all(&word, max_n, max_gap) {
+                lists.entry(woco).or_default().push(i as u32);
+            }
+            anagrams.entry(Dict::anagram_key(word)).or_default().push(i as u32);
+        }
+        Dict {
+            words,
+            scores,
+            index: Index::Built {
+                lists,
+                anagrams,
+            },
+            max_n,
+            max_gap,
+            min_len,
+            lookups: Cell::new(0),
+            matches: Cell::new(0),
+            display_forms,
+            lang,
+        }
+    }
+
+    /// Returns the letters in sorted order, which are the same for all anagrams.
+    fn anagram_key<T: IntoIterator<Item = char>>(letters: T) -> Vec<char> {
+        let mut key: Vec<char> = letters.into_iter().collect();
+        key.sort();
+        key
+    }
+
+    /// Convert the `String` to a char vector in the language's alphabet, e. g. replacing umlauts
+    /// with corresponding diphthongs in German. Return `None` if the word contains an invalid
+    /// character.
+    pub fn normalize_word<T: AsRef<str>>(str_word: T, lang: Lang) -> Option<Vec<char>> {
+        lang.normalize(str_word.as_ref())
+    }
+
+    /// Parse a line of a word list: Either just a word, or a word and its score, separated by a
+    /// semicolon, e. g. `WORD;50`. The word is normalized, and if it doesn't specify a score,
+    /// `DEFAULT_SCORE` is used. If the normalization changed more than the case of the letters,
+    /// the original form is returned, too. Return `None` if the word contains an invalid
+    /// character or the score is not an integer.
+    pub fn parse_line<T: AsRef<str>>(line: T, lang: Lang) -> Option<WordEntry> {
+        Dict::parse_tagged_line(line, lang).map(|(entry, _)| entry)
+    }
+
+    /// Parse a line of a word list like `parse_line`, which can additionally have a third field
+    /// with a comma-separated list of tags, e. g. `WORD;50;obscure` or `WORD;;trademark`. Return
+    /// `None` if any of the tags is unknown.
+    pub fn parse_tagged_line<T: AsRef<str>>(line: T, lang: Lang) -> Option<(WordEntry, Vec<Tag>)> {
+        let mut parts = line.as_ref().splitn(3, ';');
+        let str_word = parts.next()?.trim();
+        let score = match parts.next().map(str::trim) {
+            None | Some("") => DEFAULT_SCORE,
+            Some(score) => score.parse().ok()?,
+        };
+        let tags = match parts.next() {
+            None => Vec::new(),
+            Some(tags) => parse_tags(tags).ok()?,
+        };
+        let word = Dict::normalize_word(str_word, lang)?;
+        let display = if str_word.to_uppercase().chars().eq(word.iter().cloned()) {
+            None
+        } else {
+            Some(str_word.to_owned())
+        };
+        Some(((word, score, display), tags))
+    }
+
+    fn get_list(&self, wc: &WordConstraint) -> &[u32] {
+        self.index.get_list(wc)
+    }
+
+    /// Return whether the given word is present in this dictionary.
+    pub fn contains(&self, word: &[char]) -> bool {
+        self.matching_words(word).next().is_some()
+    }
+
+    /// Return the score of the given word, or `None` if it is not present in this dictionary.
+    pub fn get_score(&self, word: &[char]) -> Option<i32> {
+        self.matching_words(word).peek_score()
+    }
+
+    /// Return the original form of the given word, e. g. with spaces, hyphens or umlauts, or
+    /// `None` if the word is not in the dictionary or its normalization was not lossy.
+    pub fn get_display_form(&self, word: &[char]) -> Option<&str> {
+        let list = self.get_matching_word_list(word);
+        list.first().and_then(|&i| self.display_forms.get(&(i as usize))).map(String::as_str)
+    }
+
+    /// Return the enumeration of the given word, e. g. `(3,4)` for "New York", or `None` if it is
+    /// not in the dictionary.
+    pub fn get_enumeration(&self, word: &[char]) -> Option<String> {
+        if !self.contains(word) {
+            return None;
+        }
+        Some(match self.get_display_form(word) {
+                 Some(display) => self.lang.enumeration(display),
+                 None => format!("({})", word.len()),
+             })
+    }
+
+    /// Return the language whose profile was used to normalize the words.
+    pub fn get_lang(&self) -> Lang {
+        self.lang
+    }
+
+    /// Return the maximum length of the n-grams that are indexed.
+    pub fn get_max_n(&self) -> usize {
+        self.max_n
+    }
+
+    /// Return the maximum number of letters between the two letters of an indexed pair.
+    pub fn get_max_gap(&self) -> usize {
+        self.max_gap
+    }
+
+    /// Return the number of words in the dictionary.
+    pub fn word_count(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Return the length of the shortest word, or `usize::MAX` if the dictionary is empty.
+    pub fn get_min_len(&self) -> usize {
+        self.min_len
+    }
+
+    /// Return the number of indexed constraints.
+    pub fn list_count(&self) -> usize {
+        self.index.list_count()
+    }
+
+    /// Return the number of words satisfying the constraint, or 0 if it is not indexed. Unlike
+    /// pattern lookups, this is not counted by `lookup_counts`.
+    pub fn get_count(&self, wc: &WordConstraint) -> usize {
+        self.get_list(wc).len()
+    }
+
+    /// Return an estimate of the number of bytes occupied by the words and the index, including
+    /// the overhead of the hash maps and of the allocator.
+    pub fn memory_usage(&self) -> usize {
+        let display_forms_size: usize = self.display_forms
+            .values()
+            .map(|display| display.capacity() + ALLOCATION_OVERHEAD)
+            .sum();
+        self.words.memory_usage() + vec_memory_usage(&self.scores) +
+        hash_map_memory_usage(&self.display_forms) + display_forms_size +
+        self.index.memory_usage()
+    }
+
+    /// Return the number of pattern lookups so far, and the total number of matching words they
+    /// found.
+    pub fn lookup_counts(&self) -> (usize, usize) {
+        (self.lookups.get(), self.matches.get())
+    }
+
+    /// Return an iterator over all indexed constraints, with the number of words satisfying each
+    /// of them.
+    pub fn constraint_counts(&self) -> impl Iterator<Item = (Cow<'_, WordConstraint>, usize)> {
+        self.index.lists().map(|(wc, list)| (wc, list.len()))
+    }
+
+    /// Return an iterator over all words in the dictionary, with their scores.
+    pub fn all_scored_words(&self) -> impl Iterator<Item = (Word<'_>, i32)> {
+        self.words.iter().zip(self.scores.iter().cloned())
+    }
+
+    /// Return an iterator over all words in the dictionary, with their scores and display forms,
+    /// if they differ from the words.
+    pub fn all_entries(&self) -> impl Iterator<Item = (Word<'_>, i32, Option<&str>)> {
+        self.all_scored_words()
+            .enumerate()
+            .map(move |(i, (word, score))| {
+                     (word, score, self.display_forms.get(&i).map(String::as_str))
+                 })
+    }
+
+    /// Return an iterator over all words in the dictionary.
+    pub fn all_words(&self) -> WordListIter<'_> {
+        self.words.iter()
+    }
+
+    /// Returns the lists of all words satisfying constraints that together cover every letter of
+    /// the pattern. If the pattern has no letters, this is just the list of words with its length.
+    fn get_constraint_lists(&self, pattern: &[char]) -> Vec<&[u32]> {
+        let len = pattern.len();
+        let mut lists = Vec::new();
+        let mut pos = 0;
+        let mut prev_last = None;
+        for i in pattern
+                .iter()
+                .enumerate()
+                .filter(|&(_, ch)| ch == &BLOCK)
+                .map(|(i, _)| i)
+                .chain(iter::once(len)) {
+            if i > pos {
+                let subword = &pattern[pos..i];
+                let n = cmp::min(self.max_n, subword.len());
+                let last = subword.len() - n;
+                for dp in (0..last).step_by(n).chain(iter::once(last)) {
+                    let wc = WordConstraint::with_ngram(&subword[dp..(dp + n)], pos + dp, len);
+                    lists.push(self.get_list(&wc));
+                }
+                // Link the subword to the previous one with the pair of letters around the gap.
+                if let Some(j) = prev_last {
+                    if pos - j - 1 <= self.max_gap {
+                        let wc = WordConstraint::PairAt([pattern[j], pattern[pos]], j, pos, len);
+                        lists.push(self.get_list(&wc));
+                    }
+                }
+                prev_last = Some(i - 1);
+            }
+            pos = i + 1;
+        }
+        if lists.is_empty() {
+            lists.push(self.get_list(&WordConstraint::Length(len)));
+        }
+        lists
+    }
+
+    /// Returns the sorted list of the indices of all words matching the pattern: the intersection
+    /// of the lists for all constraints that cover the pattern's letters.
+    fn get_matching_word_list(&self, pattern: &[char]) -> Cow<'_, [u32]> {
+        let mut lists = self.get_constraint_lists(pattern);
+        lists.sort_by_key(|list| list.len());
+        lists.dedup();
+        let list = if lists.len() == 1 || lists[0].is_empty() {
+            Cow::Borrowed(lists[0])
+        } else {
+            Cow::Owned(intersect(&lists))
+        };
+        self.lookups.set(self.lookups.get() + 1);
+        self.matches.set(self.matches.get() + list.len());
+        list
+    }
+
+    /// Return an iterator over all words in the dictionary matching the given pattern, in
+    /// descending order of their scores.
+    pub fn matching_words(&self, pattern: &[char]) -> PatternIter {
+        PatternIter {
+            dict: self,
+            list: self.get_matching_word_list(pattern),
+            index: 0,
+        }
+    }
+
+    /// Return an iterator over the words matching the pattern, like `matching_words`, but only
+    /// those whose indices are in `allowed`, which must be sorted.
+    pub fn matching_words_among(&self, pattern: &[char], allowed: &[u32]) -> PatternIter<'_> {
+        let list = intersect(&[allowed, &self.get_matching_word_list(pattern)]);
+        PatternIter {
+            dict: self,
+            list: Cow::Owned(list),
+            index: 0,
+        }
+    }
+
+    /// Return an iterator over all words in the dictionary that consist of exactly the given
+    /// letters, in descending order of their scores. Each `BLOCK` among the letters is a blank that
+    /// stands for any letter. If `sub` is true, words that use only some of the letters are
+    /// included, too.
+    pub fn anagrams(&self, letters: &[char], sub: bool) -> PatternIter {
+        let blanks = letters.iter().filter(|&&c| c == BLOCK).count();
+        let key = Dict::anagram_key(letters.iter().cloned().filter(|&c| c != BLOCK));
+        let list = if blanks == 0 && !sub {
+            Cow::Borrowed(self.index.get_anagram_list(&key))
+        } else {
+            let mut list: Vec<u32> = self.index
+                .anagram_lists()
+                .filter(|(word_key, _)| {
+                            (word_key.len() == letters.len() ||
+                             sub && word_key.len() < letters.len()) &&
+                            missing_letters(word_key, &key) <= blanks
+                        })
+                .flat_map(|(_, list)| list.iter().cloned())
+                .collect();
+            list.sort();
+            Cow::Owned(list)
+        };
+        PatternIter {
+            dict: self,
+            list,
+            index: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    #[cfg(feature = "nightly")]
+    use test::Bencher;
+    use test_util::*;
+
+    #[test]
+    fn test() {
+        let dict = words_to_dict(strs_to_cvecs(&["FOO", "FOOBAR", "FOE", "TOE"]));
+        assert_eq!(2, dict.matching_words(&str_to_cvec("#OE")).count());
+        assert_eq!(1, dict.matching_words(&str_to_cvec("F#E")).count());
+        assert_eq!(0, dict.matching_words(&str_to_cvec("T#O")).count());
+        assert_eq!(0, dict.matching_words(&str_to_cvec("F###")).count());
+        assert_eq!(0, dict.matching_words(&str_to_cvec("##")).count());
+    }
+
+    #[test]
+    fn test_intersect() {
+        let list0 = [1, 2, 5, 7, 9];
+        let list1 = [0, 2, 3, 5, 6, 8, 9, 10];
+        let list2 = [2, 4, 9, 11, 12, 13, 14, 15, 16, 17];
+        assert_eq!(vec![2, 5, 9], intersect(&[&list0, &list1]));
+        assert_eq!(vec![2, 9], intersect(&[&list0, &list1, &list2]));
+        assert_eq!(Vec::<u32>::new(), intersect(&[&[1, 3], &[0, 2, 4]]));
+    }
+
+    #[test]
+    fn test_matching_words() {
+        let dict = words_to_dict(strs_to_cvecs(&["ABCDEFG", "ABCXEFG", "ABCDEFX", "XBCDEFG"]));
+        let words = dict.matching_words(&str_to_cvec("A#C#E#G"))
+            .map(Word::to_vec)
+            .collect_vec();
+        assert_eq!(strs_to_cvecs(&["ABCDEFG", "ABCXEFG"]),
+                   words.into_iter().sorted());
+        assert_eq!(1, dict.matching_words(&str_to_cvec("ABCD#FG")).count());
+        assert_eq!(0, dict.matching_words(&str_to_cvec("ABCDEFGH")).count());
+    }
+
+    #[test]
+    fn test_anagrams() {
+        let dict = scored_dict(vec![(str_to_cvec("LISTEN"), 10),
+                                    (str_to_cvec("SILENT"), 20),
+                                    (str_to_cvec("TINSEL"), 30),
+                                    (str_to_cvec("LIST"), 40),
+                                    (str_to_cvec("STILE"), 50),
+                                    (str_to_cvec("LEST"), 60)]);
+        let anagrams = |letters, sub| {
+            dict.anagrams(&str_to_cvec(letters), sub).map(Word::to_vec).collect_vec()
+        };
+        assert_eq!(strs_to_cvecs(&["TINSEL", "SILENT", "LISTEN"]), anagrams("ENLIST", false));
+        assert_eq!(strs_to_cvecs(&["STILE"]), anagrams("ISLET", false));
+        assert_eq!(strs_to_cvecs(&["LEST", "STILE", "LIST"]), anagrams("ISLT#", true));
+        assert_eq!(strs_to_cvecs(&["LIST"]), anagrams("TSIL", true));
+        assert_eq!(strs_to_cvecs(&["LEST", "STILE", "LIST", "TINSEL", "SILENT", "LISTEN"]),
+                   anagrams("LIST##", true));
+        assert_eq!(strs_to_cvecs(&["TINSEL", "SILENT", "LISTEN"]), anagrams("N#S#T#", false));
+        assert!(anagrams("ENLISTS", false).is_empty());
+    }
+
+    #[test]
+    fn test_pair_lists() {
+        let dict = words_to_dict(strs_to_cvecs(&["ABCDE", "AXEYS", "XBEYS"]));
+        let lists = dict.get_constraint_lists(&str_to_cvec("A#E#S"));
+        assert_eq!(5, lists.len());
+        assert_eq!(1, dict.matching_words(&str_to_cvec("A#E#S")).count());
+        assert_eq!(0, dict.matching_words(&str_to_cvec("X#C##")).count());
+        // Without pairs, only the letters themselves are looked up.
+        let entries = strs_to_cvecs(&["ABCDE", "AXEYS", "XBEYS"]).into_iter().map(|w| (w, 0, None));
+        let dict = Dict::from_entries(entries, 3, 0, Lang::default());
+        assert_eq!(3, dict.get_constraint_lists(&str_to_cvec("A#E#S")).len());
+        assert_eq!(1, dict.matching_words(&str_to_cvec("A#E#S")).count());
+    }
+
+    #[test]
+    fn test_max_n() {
+        let words = strs_to_cvecs(&["ABCDE", "AXEYS", "XBEYS", "ABCXY"]);
+        let new_dict = |max_n| {
+            let entries = words.iter().map(|w| (w.clone(), 0, None));
+            Dict::from_entries(entries, max_n, DEFAULT_MAX_GAP, Lang::default())
+        };
+        let (dict1, dict3) = (new_dict(1), new_dict(3));
+        assert!(dict1.list_count() < dict3.list_count());
+        assert!(dict1.memory_usage() < dict3.memory_usage());
+        for pattern in &["ABC##", "A#E#S", "#####", "X####"] {
+            let pattern = str_to_cvec(pattern);
+            assert_eq!(dict3.matching_words(&pattern).count(),
+                       dict1.matching_words(&pattern).count());
+        }
+        assert_eq!((4, 8), dict1.lookup_counts());
+    }
+
+    #[test]
+    fn test_scores() {
+        let dict = scored_dict(vec![(str_to_cvec("FOO"), 10),
+                                    (str_to_cvec("FOE"), 60),
+                                    (str_to_cvec("TOE"), 30),
+                                    (str_to_cvec("FOE"), 20)]);
+        let words = dict.matching_words(&str_to_cvec("###")).map(Word::to_vec).collect_vec();
+        assert_eq!(strs_to_cvecs(&["FOE", "TOE", "FOO"]), words);
+        assert_eq!(Some(60), dict.get_score(&str_to_cvec("FOE")));
+        assert_eq!(None, dict.get_score(&str_to_cvec("BAR")));
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let parse = |line| Dict::parse_line(line, Lang::default());
+        assert_eq!(Some((str_to_cvec("FOO"), DEFAULT_SCORE, None)), parse("foo"));
+        assert_eq!(Some((str_to_cvec("FOO"), 25, None)), parse("Foo;25"));
+        assert_eq!(Some((str_to_cvec("NEWYORK"), 25, Some("New York".to_owned()))),
+                   parse(" New York ;25"));
+        assert_eq!(None, parse("foo;bar"));
+        assert_eq!(None, parse("f;o;o;25"));
+        assert_eq!(Some((str_to_cvec("FOO"), DEFAULT_SCORE, None)), parse("foo;"));
+        let parse_tagged = |line| Dict::parse_tagged_line(line, Lang::default());
+        assert_eq!(Some(((str_to_cvec("FOO"), 25, None), vec![Tag::Obscure, Tag::Trademark])),
+                   parse_tagged("foo;25;obscure, trademark"));
+        assert_eq!(Some(((str_to_cvec("FOO"), DEFAULT_SCORE, None), vec![Tag::Offensive])),
+                   parse_tagged("foo;;offensive"));
+        assert_eq!(None, parse_tagged("foo;25;boring"));
+    }
+
+    #[test]
+    fn test_display_forms() {
+        let lines = vec!["New York;60", "Düsenjäger", "Foo", "NEW-YORK"];
+        let entries = lines.into_iter().filter_map(|line| Dict::parse_line(line, Lang::German));
+        let dict = Dict::from_entries(entries, 2, 1, Lang::German);
+        assert_eq!(3, dict.word_count());
+        assert_eq!(Some("New York"), dict.get_display_form(&str_to_cvec("NEWYORK")));
+        assert_eq!(Some("Düsenjäger"), dict.get_display_form(&str_to_cvec("DUESENJAEGER")));
+        assert_eq!(None, dict.get_display_form(&str_to_cvec("FOO")));
+        assert_eq!(Some("(3,4)".to_owned()), dict.get_enumeration(&str_to_cvec("NEWYORK")));
+        assert_eq!(Some("(3)".to_owned()), dict.get_enumeration(&str_to_cvec("FOO")));
+        assert_eq!(None, dict.get_enumeration(&str_to_cvec("BAR")));
+    }
+
+    #[test]
+    fn test_normalize_word() {
+        let words = vec!["Öha", "Düsenjäger", "H4X0R", "Wow!", "Fuß", "Café"]
+            .into_iter()
+            .filter_map(|word| Dict::normalize_word(word, Lang::German))
+            .collect_vec();
+        let expected = strs_to_cvecs(&["OEHA", "DUESENJAEGER", "FUSS", "CAFE"]);
+        assert_eq!(expected, words);
+    }
+
+    /// Returns a dictionary with 200000 pseudorandom words of length 4 to 11.
+    #[cfg(feature = "nightly")]
+    fn create_large_dict() -> Dict {
+        let letters: Vec<char> = "ABCDEFGHIKLMNOPRSTUE".chars().collect();
+        let mut state: u64 = 1;
+        let mut next = move |n: usize| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((state >> 33) as usize) % n
+        };
+        words_to_dict((0..200000).map(|_| {
+                                      let len = 4 + next(8);
+                                      (0..len).map(|_| letters[next(letters.len())]).collect_vec()
+                                  }))
+    }
+
+    #[cfg(feature = "nightly")]
+    const BENCH_PATTERNS: &[&str] = &["A###E##S", "#R####T##", "S#####", "#####ER", "MA#E#T"];
+
+    #[cfg(feature = "nightly")]
+    #[bench]
+    fn bench_matching_words(bencher: &mut Bencher) {
+        let dict = create_large_dict();
+        let patterns = BENCH_PATTERNS.iter().map(str_to_cvec).collect_vec();
+        bencher.iter(|| {
+                         patterns
+                             .iter()
+                             .map(|pattern| dict.matching_words(pattern).count())
+                             .sum::<usize>()
+                     });
+    }
+
+    /// The approach without intersections, for comparison: Check every word in the shortest
+    /// list.
+    #[cfg(feature = "nightly")]
+    #[bench]
+    fn bench_matching_words_in_shortest_list(bencher: &mut Bencher) {
+        let dict = create_large_dict();
+        let patterns = BENCH_PATTERNS.iter().map(str_to_cvec).collect_vec();
+        let count = |pattern: &[char]| {
+            let lists = dict.get_constraint_lists(pattern);
+            let list = lists.into_iter().min_by_key(|list| list.len()).unwrap();
+            list.iter()
+                .filter(|&&i| {
+                            dict.words
+                                .get(i as usize)
+                                .chars()
+                                .zip(pattern)
+                                .all(|(cw, &cp)| cw == cp || cp == BLOCK)
+                        })
+                .count()
+        };
+        for pattern in &patterns {
+            assert_eq!(dict.matching_words(pattern).count(), count(pattern));
+        }
+        bencher.iter(|| patterns.iter().map(|pattern| count(pattern)).sum::<usize>());
+    }
+}
@@ -0,0 +1,126 @@
+use dict::{Dict, Lang};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Result};
+use std::path::Path;
+
+/// The minimum number of letters that a suffix-stripping stem must keep.
+const MIN_STEM_LEN: usize = 3;
+
+/// The minimum length of a word for which longer words containing it count as related.
+const MIN_CONTAINED_LEN: usize = 3;
+
+/// Decides whether two words are morphologically related, e. g. `RUN` and `RUNNING` or `HAUS` and
+/// `HAEUSER`, so that they shouldn't appear in the same grid.
+pub struct Stemmer {
+    lang: Lang,
+    lemmas: HashMap<Vec<char>, Vec<char>>,
+}
+
+impl Stemmer {
+    /// Creates a stemmer that strips the suffixes of the language.
+    pub fn new(lang: Lang) -> Stemmer {
+        Stemmer {
+            lang,
+            lemmas: HashMap::new(),
+        }
+    }
+
+    /// Reads a lemma file: Each line contains a lemma followed by some of its forms, separated by
+    /// whitespace, e. g. `go went gone`. The forms of a lemma are considered related to each other
+    /// instead of using the suffix-stripping stems.
+    pub fn read_lemmas<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        for line in BufReader::new(File::open(path)?).lines() {
+            self.add_lemmas(&line?);
+        }
+        Ok(())
+    }
+
+    /// Adds a line in the format of the lemma file.
+    pub fn add_lemmas(&mut self, line: &str) {
+        let lang = self.lang;
+        let mut words = line.split_whitespace().filter_map(|w| Dict::normalize_word(w, lang));
+        if let Some(lemma) = words.next() {
+            for form in words {
+                self.lemmas.insert(form, lemma.clone());
+            }
+            self.lemmas.insert(lemma.clone(), lemma);
+        }
+    }
+
+    /// Returns the stem of the normalized word: its lemma, if it is in the lemma file, otherwise
+    /// the word with the longest suffix removed that keeps at least `MIN_STEM_LEN` letters.
+    pub fn stem(&self, word: &[char]) -> Vec<char> {
+        if let Some(lemma) = self.lemmas.get(word) {
+            return lemma.clone();
+        }
+        let word: String = word.iter().collect();
+        let mut stem = self.lang
+            .suffixes()
+            .iter()
+            .filter(|suffix| word.ends_with(*suffix))
+            .map(|suffix| &word[..(word.len() - suffix.len())])
+            .find(|stem| stem.chars().count() >= MIN_STEM_LEN)
+            .unwrap_or(&word)
+            .to_owned();
+        match self.lang {
+            // Umlauts in plurals and comparatives: `HAEUS` for `HAEUSER` becomes `HAUS`.
+            Lang::German => stem = stem.replace("AE", "A").replace("OE", "O").replace("UE", "U"),
+            // `TRIES` becomes `TRI`, so `TRY` has to become `TRI`, too.
+            Lang::English if stem.ends_with('Y') => {
+                stem.pop();
+                stem.push('I');
+            }
+            _ => (),
+        }
+        let mut chars: Vec<char> = stem.chars().collect();
+        // A doubled final consonant, as in `RUNNING`, is reduced to one letter.
+        if let [.., c0, c1] = chars[..] {
+            if c0 == c1 && !"AEIOU".contains(c0) {
+                chars.pop();
+            }
+        }
+        chars
+    }
+
+    /// Returns whether one of the words contains the other, unless that has fewer than
+    /// `MIN_CONTAINED_LEN` letters. Together with a shared stem, this makes two words related.
+    pub fn is_contained(word0: &[char], word1: &[char]) -> bool {
+        let (short, long) = if word0.len() <= word1.len() {
+            (word0, word1)
+        } else {
+            (word1, word0)
+        };
+        short.len() >= MIN_CONTAINED_LEN && long.windows(short.len()).any(|w| w == short)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::*;
+
+    #[test]
+    fn test_related() {
+        let related = |stemmer: &Stemmer, w0, w1| {
+            let (word0, word1) = (str_to_cvec(w0), str_to_cvec(w1));
+            stemmer.stem(&word0) == stemmer.stem(&word1) || Stemmer::is_contained(&word0, &word1)
+        };
+        let stemmer = Stemmer::new(Lang::English);
+        assert_eq!(str_to_cvec("RUN"), stemmer.stem(&str_to_cvec("RUNNING")));
+        assert!(related(&stemmer, "RUN", "RUNNING"));
+        assert!(related(&stemmer, "TRIES", "TRY"));
+        assert!(related(&stemmer, "CARRIES", "CARRY"));
+        assert!(related(&stemmer, "HOPED", "HOPES"));
+        assert!(!related(&stemmer, "HOPE", "HOOP"));
+        assert!(!related(&stemmer, "WENT", "GOES"));
+        assert!(!related(&stemmer, "AN", "MAN"));
+        let mut stemmer = Stemmer::new(Lang::English);
+        stemmer.add_lemmas("go went gone goes");
+        assert!(related(&stemmer, "WENT", "GOES"));
+        let stemmer = Stemmer::new(Lang::German);
+        assert!(related(&stemmer, "HAUS", "HAEUSER"));
+        assert!(related(&stemmer, "MAUS", "MAEUSE"));
+        assert!(!related(&stemmer, "MAUS", "MOOS"));
+    }
+}
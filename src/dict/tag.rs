@@ -0,0 +1,42 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A category that an entry of a word list can be tagged with, so that it can be excluded from
+/// the puzzles.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Tag {
+    /// Vulgar or derogatory words.
+    Offensive,
+    /// Brand and product names.
+    Trademark,
+    /// Rare words that most solvers won't know.
+    Obscure,
+}
+
+impl FromStr for Tag {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Tag, String> {
+        match s {
+            "offensive" => Ok(Tag::Offensive),
+            "trademark" => Ok(Tag::Trademark),
+            "obscure" => Ok(Tag::Obscure),
+            _ => Err(format!("unknown tag: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+                        Tag::Offensive => "offensive",
+                        Tag::Trademark => "trademark",
+                        Tag::Obscure => "obscure",
+                    })
+    }
+}
+
+/// Parses a comma-separated list of tags, e. g. `offensive,obscure`.
+pub fn parse_tags(s: &str) -> Result<Vec<Tag>, String> {
+    s.split(',').map(str::trim).filter(|tag| !tag.is_empty()).map(str::parse).collect()
+}
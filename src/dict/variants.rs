@@ -0,0 +1,87 @@
+use dict::{Dict, Lang};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Result};
+use std::path::Path;
+
+/// Groups of equivalent words, e. g. variant spellings like `COLOR` and `COLOUR`, of which a
+/// puzzle should contain at most one.
+pub struct VariantGroups {
+    lang: Lang,
+    groups: HashMap<Vec<char>, usize>,
+    group_count: usize,
+}
+
+impl VariantGroups {
+    /// Creates an empty set of groups for words in the given language.
+    pub fn new(lang: Lang) -> VariantGroups {
+        VariantGroups {
+            lang,
+            groups: HashMap::new(),
+            group_count: 0,
+        }
+    }
+
+    /// Reads a file with one group per line, its words separated by whitespace.
+    pub fn read_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        for line in BufReader::new(File::open(path)?).lines() {
+            self.add_group(&line?);
+        }
+        Ok(())
+    }
+
+    /// Adds a group in the format of the file. If one of its words is already in a group, the two
+    /// groups are merged.
+    pub fn add_group(&mut self, line: &str) {
+        let lang = self.lang;
+        let words: Vec<Vec<char>> =
+            line.split_whitespace().filter_map(|w| Dict::normalize_word(w, lang)).collect();
+        if words.len() < 2 {
+            return;
+        }
+        let existing: Vec<usize> =
+            words.iter().filter_map(|w| self.groups.get(w)).cloned().collect();
+        let group = match existing.first() {
+            Some(&group) => group,
+            None => {
+                self.group_count += 1;
+                self.group_count - 1
+            }
+        };
+        for id in self.groups.values_mut().filter(|id| existing.contains(id)) {
+            *id = group;
+        }
+        self.groups.extend(words.into_iter().map(|word| (word, group)));
+    }
+
+    /// Returns whether the words are different members of the same group.
+    pub fn are_variants(&self, word0: &[char], word1: &[char]) -> bool {
+        word0 != word1 &&
+        match (self.groups.get(word0), self.groups.get(word1)) {
+            (Some(group0), Some(group1)) => group0 == group1,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::*;
+
+    #[test]
+    fn test_are_variants() {
+        let mut variants = VariantGroups::new(Lang::German);
+        variants.add_group("Photographie Fotografie");
+        variants.add_group("daß dasz");
+        variants.add_group("Fotografie Fotographie");
+        variants.add_group("Haus");
+        let are_variants = |w0, w1| variants.are_variants(&str_to_cvec(w0), &str_to_cvec(w1));
+        assert!(are_variants("PHOTOGRAPHIE", "FOTOGRAFIE"));
+        assert!(are_variants("DASS", "DASZ"));
+        assert!(are_variants("PHOTOGRAPHIE", "FOTOGRAPHIE"));
+        assert!(!are_variants("FOTOGRAFIE", "DASS"));
+        assert!(!are_variants("DASS", "DASS"));
+        assert!(!are_variants("HAUS", "DASS"));
+    }
+}
@@ -0,0 +1,207 @@
+//! Compact storage for the words of a dictionary. Instead of a `Vec<char>` per word, all words
+//! are stored in a single buffer, with each letter encoded as its index in the dictionary's
+//! alphabet. Words are handed out as `Word` references into that buffer, so iterating over
+//! candidate words doesn't allocate.
+//!
+//! The encoding is private to each `Dict`: Every dictionary has its own alphabet, so a `Word` is
+//! only meaningful together with its list. Everything outside the dictionaries - the words placed
+//! in a `Crosswords` grid, and the constraints counted by `WordStats` - still uses `char`s, and a
+//! `Word` has to be decoded to be placed.
+
+use dict::{hash_map_memory_usage, vec_memory_usage};
+use std::collections::HashMap;
+use std::fmt;
+use std::iter::FromIterator;
+use std::slice;
+
+/// The index of a letter in the alphabet.
+type Code = u16;
+
+/// A list of words over a common alphabet, stored in a single buffer.
+pub struct WordList {
+    /// The letters that occur in the words, indexed by their codes.
+    alphabet: Vec<char>,
+    codes: HashMap<char, Code>,
+    /// The codes of all letters of all words, concatenated.
+    buffer: Vec<Code>,
+    /// The end of each word in the buffer.
+    ends: Vec<u32>,
+}
+
+impl WordList {
+    /// Creates an empty word list.
+    pub fn new() -> WordList {
+        WordList {
+            alphabet: Vec::new(),
+            codes: HashMap::new(),
+            buffer: Vec::new(),
+            ends: Vec::new(),
+        }
+    }
+
+    fn code(&mut self, c: char) -> Code {
+        if let Some(&code) = self.codes.get(&c) {
+            return code;
+        }
+        if self.alphabet.len() > Code::MAX as usize {
+            panic!("too many different letters in the dictionary");
+        }
+        let code = self.alphabet.len() as Code;
+        self.alphabet.push(c);
+        self.codes.insert(c, code);
+        code
+    }
+
+    /// Appends the word to the end of the list.
+    pub fn push(&mut self, word: &[char]) {
+        for &c in word {
+            let code = self.code(c);
+            self.buffer.push(code);
+        }
+        self.ends.push(self.buffer.len() as u32);
+    }
+
+    /// Returns the number of words in the list.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns the word with the given index.
+    pub fn get(&self, i: usize) -> Word<'_> {
+        let start = if i == 0 { 0 } else { self.ends[i - 1] as usize };
+        Word {
+            alphabet: &self.alphabet,
+            codes: &self.buffer[start..(self.ends[i] as usize)],
+        }
+    }
+
+    /// Returns an iterator over all words in the list.
+    pub fn iter(&self) -> WordListIter<'_> {
+        WordListIter {
+            list: self,
+            index: 0,
+        }
+    }
+
+    /// Returns an estimate of the number of bytes occupied by the list.
+    pub fn memory_usage(&self) -> usize {
+        vec_memory_usage(&self.alphabet) + hash_map_memory_usage(&self.codes) +
+        vec_memory_usage(&self.buffer) + vec_memory_usage(&self.ends)
+    }
+}
+
+impl<'a> FromIterator<&'a [char]> for WordList {
+    fn from_iter<T: IntoIterator<Item = &'a [char]>>(words: T) -> WordList {
+        let mut list = WordList::new();
+        for word in words {
+            list.push(word);
+        }
+        list
+    }
+}
+
+/// An iterator over all words in a `WordList`.
+pub struct WordListIter<'a> {
+    list: &'a WordList,
+    index: usize,
+}
+
+impl<'a> Iterator for WordListIter<'a> {
+    type Item = Word<'a>;
+
+    fn next(&mut self) -> Option<Word<'a>> {
+        if self.index >= self.list.len() {
+            return None;
+        }
+        self.index += 1;
+        Some(self.list.get(self.index - 1))
+    }
+}
+
+/// A reference to a word in a `WordList`.
+#[derive(Clone, Copy)]
+pub struct Word<'a> {
+    alphabet: &'a [char],
+    codes: &'a [Code],
+}
+
+impl<'a> Word<'a> {
+    /// Returns an iterator over the word's letters.
+    pub fn chars(self) -> Chars<'a> {
+        Chars {
+            alphabet: self.alphabet,
+            codes: self.codes.iter(),
+        }
+    }
+
+    /// Decodes the word into a char vector.
+    pub fn to_vec(self) -> Vec<char> {
+        self.chars().collect()
+    }
+}
+
+impl<'a> IntoIterator for Word<'a> {
+    type Item = char;
+    type IntoIter = Chars<'a>;
+
+    fn into_iter(self) -> Chars<'a> {
+        self.chars()
+    }
+}
+
+impl<'a, 'b> PartialEq<Word<'b>> for Word<'a> {
+    fn eq(&self, other: &Word<'b>) -> bool {
+        self.chars().eq(other.chars())
+    }
+}
+
+impl<'a> PartialEq<[char]> for Word<'a> {
+    fn eq(&self, other: &[char]) -> bool {
+        self.chars().eq(other.iter().cloned())
+    }
+}
+
+impl<'a> fmt::Debug for Word<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.chars().collect::<String>())
+    }
+}
+
+/// An iterator over the letters of a `Word`.
+pub struct Chars<'a> {
+    alphabet: &'a [char],
+    codes: slice::Iter<'a, Code>,
+}
+
+impl<'a> Iterator for Chars<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        self.codes.next().map(|&code| self.alphabet[code as usize])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.codes.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for Chars<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::*;
+
+    #[test]
+    fn test_word_list() {
+        let words = strs_to_cvecs(&["FOO", "", "BAR"]);
+        let list: WordList = words.iter().map(|word| &word[..]).collect();
+        assert_eq!(3, list.len());
+        assert_eq!(5, list.alphabet.len());
+        assert_eq!(6, list.buffer.len());
+        assert_eq!(0, list.get(1).chars().count());
+        assert_eq!(words, list.iter().map(|word| word.to_vec()).collect::<Vec<_>>());
+        assert!(list.get(2) == words[2][..]);
+        assert!(list.get(0) != list.get(2));
+    }
+}
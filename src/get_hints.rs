@@ -3,6 +3,7 @@
 //       https://github.com/bwbaugh/wikipedia-extractor/blob/master/WikiExtractor.py
 //       It might be best to move the whole hint generation out of crosswords-rs. Instead, you
 //       could specify a hint generation program at the command line.
+use regex;
 use regex::Regex;
 use std::collections::HashMap;
 
@@ -49,7 +50,7 @@ fn get_hint_from_article(article: String, word: &str, lang: &str) -> String {
         "en" => " is | are | was | were ",
         _ => unimplemented!(),
     };
-    let word_re = format!(r#"((?i){})"#, word);
+    let word_re = format!(r#"((?i){})"#, regex::escape(word));
     // Disambiguations:
     let ex_re1 = Regex::new(&format!(
             r#"{}\S* (or [^\.\n]* )?may refer to:\n(\s*((=|;).*|.*:)?\n)*\*(?P<excerpt>.*)\n"#,
@@ -76,7 +77,7 @@ fn get_hint_from_article(article: String, word: &str, lang: &str) -> String {
     };
     replace_all(excerpt,
                 vec![// Replace the word from the crosswords with ellipses.
-                     (&format!(r#"(?i){}"#, &word), "..."),
+                     (&format!(r#"(?i){}"#, regex::escape(word)), "..."),
                      // Replace any sequence of whitespace with a single space.
                      (r#"\s+"#, " ")])
             .trim()
@@ -92,7 +93,13 @@ fn download_from(url: &str) -> String {
 }
 
 fn download_article(word: &str, lang: &str) -> String {
-    let cased_word = format!("{}{}", &word[..1], word[1..].to_lowercase());
+    // Display forms from the dictionary are already cased correctly.
+    let cased_word = if word.chars().any(char::is_lowercase) {
+        word.replace(" ", "_")
+    } else {
+        let mut chars = word.chars();
+        chars.next().into_iter().chain(chars.flat_map(char::to_lowercase)).collect()
+    };
     let url = format!("http://{}.wikipedia.org/w/index.php?title={}&action=raw",
                       lang,
                       cased_word);
@@ -121,7 +128,6 @@ fn get_hint(word: &str, lang: &str) -> String {
     // TODO: Handle disambiguations.
     // TODO: Do something (like, an anagram?) if the article doesn't exist.
     // TODO: Handle all errors without panic!
-    // TODO: Restore umlauts.
     get_hint_from_article(article, word, lang)
 }
 
@@ -1,4 +1,5 @@
-use cw::{Crosswords, Dir, Point, PrintItem};
+use cw::{Crosswords, Dir, Entry, Numbering, PrintItem};
+use dict::Dict;
 use std::collections::HashMap;
 use std::io::{Result, Write};
 
@@ -43,13 +44,12 @@ fn string_for(item: &PrintItem, solution: bool) -> String {
         PrintItem::Cross(b) => format!(r#"<div class="low {}"></div>"#, get_border_class(b)),
         PrintItem::VertBorder(b) => format!(r#"<div class="high {}"></div>"#, get_border_class(b)),
         PrintItem::Block => r#"<div class="high blockcol"></div>"#.to_string(),
-        PrintItem::CharHint(c, hint) => {
+        PrintItem::CharHint(c, ref hint) => {
             format!(concat!(r#"<div class = "high">"#,
                             r#"<span class="hint">{}</span>"#,
                             r#"<span class="solution">{}</span>"#,
                             r#"</div>"#),
-                    hint.map(|h| h.to_string())
-                        .unwrap_or_else(|| "".to_owned()),
+                    hint.as_ref().map_or("", |h| h.as_str()),
                     if solution {
                         c.to_string()
                     } else {
@@ -73,7 +73,7 @@ fn write_grid<T: Write, I: Iterator<Item = PrintItem>>(writer: &mut T,
 }
 
 fn write_hints<T: Write>(writer: &mut T,
-                         cw: &Crosswords,
+                         entries: &[Entry],
                          dir: Dir,
                          hint_text: &HashMap<String, String>)
                          -> Result<()> {
@@ -83,31 +83,29 @@ fn write_hints<T: Write>(writer: &mut T,
                       Dir::Right => "Horizontal",
                       Dir::Down => "Vertical",
                   }));
-    let mut hint_count = 0;
-    for y in 0..cw.get_height() {
-        for x in 0..cw.get_width() {
-            let p = Point::new(x as i32, y as i32);
-            if cw.has_hint_at(p) {
-                hint_count += 1;
-            }
-            if cw.has_hint_at_dir(p, dir) {
-                let word: String = cw.chars_at(p, dir).collect();
-                let hint = hint_text
-                    .get(&word)
-                    .cloned()
-                    .unwrap_or_else(|| format!("[{}]", word));
-                try!(write!(writer, "<b>{}.</b> {} &nbsp;", hint_count, hint));
-            }
-        }
+    for entry in entries.iter().filter(|e| e.dir == dir) {
+        let hint = hint_text
+            .get(&entry.display)
+            .cloned()
+            .unwrap_or_else(|| format!("[{}]", entry.display));
+        try!(write!(writer,
+                    "<b>{}.</b> {} {} &nbsp;",
+                    entry.label,
+                    hint,
+                    entry.enumeration));
     }
     try!(writeln!(writer, "</p>"));
     Ok(())
 }
 
-/// Write the crosswords to the given writer as an HTML page.
+/// Write the crosswords to the given writer as an HTML page, labeling the clues according to the
+/// given numbering scheme. The hints are looked up by the words' display forms in the given
+/// dictionaries.
 pub fn write_html<T: Write>(writer: &mut T,
                             cw: &Crosswords,
+                            dicts: &[Dict],
                             solution: bool,
+                            numbering: Numbering,
                             hint_text: &HashMap<String, String>)
                             -> Result<()> {
     try!(writeln!(writer, r#"<!doctype html>"#));
@@ -119,10 +117,11 @@ pub fn write_html<T: Write>(writer: &mut T,
     try!(writeln!(writer,
                   r#"<div style="width: {}px">"#,
                   cw.get_width() * 32 + 2));
-    try!(write_grid(writer, cw.print_items(), solution));
+    try!(write_grid(writer, cw.print_items(numbering), solution));
     try!(writeln!(writer, r#"</div><br><div style="clear: both"></div>"#));
-    try!(write_hints(writer, cw, Dir::Right, hint_text));
-    try!(write_hints(writer, cw, Dir::Down, hint_text));
+    let entries = cw.entries(dicts, numbering);
+    try!(write_hints(writer, &entries, Dir::Right, hint_text));
+    try!(write_hints(writer, &entries, Dir::Down, hint_text));
     try!(writeln!(writer, "<br></body>"));
     Ok(())
 }
@@ -1,6 +1,7 @@
 #![cfg_attr(feature = "nightly", feature(test))]
 extern crate getopts;
 extern crate itertools;
+extern crate memmap;
 extern crate hyper;
 extern crate regex;
 extern crate rand;
@@ -11,55 +12,95 @@ mod author;
 mod cw;
 mod dict;
 mod word_constraint;
+mod word_filter;
 mod word_stats;
 #[cfg(test)]
 mod test_util;
 
-use getopts::Options;
+use getopts::{Matches, Options};
+use itertools::Itertools;
+use std::cmp;
 use std::collections::HashMap;
 use std::env;
 use std::i32;
+use std::iter;
 
 mod html;
 mod get_hints;
+mod query;
 
-use author::Author;
-use cw::Crosswords;
-use dict::Dict;
+use author::{Author, CrissCross, SlotConstraints};
+use cw::{Crosswords, Dir, Numbering};
+use dict::{DEFAULT_MAX_GAP, DEFAULT_MAX_N, DEFAULT_SCORE, Dict, Lang, Stemmer, Tag, VariantGroups,
+           WordCounts, WordEntry, parse_tags};
 use get_hints::get_hints;
-use std::collections::HashSet;
 use std::fs::File;
-use std::io::{BufRead, BufReader, BufWriter, Result};
+use std::io::{BufRead, BufReader, BufWriter, Read, Result, Write};
 use std::path::Path;
+use std::process;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 use std::usize;
+use query::{Query, QueryOrder};
+use rand::XorShiftRng;
+use word_filter::{Rejection, RejectionCounts, WordFilter};
+use word_stats::WordStats;
 
 /// Write the crosswords grid to the file with the given name.
 fn write_html_to_file<P: AsRef<Path>>(filename: P,
                                       cw: &Crosswords,
+                                      dicts: &[Dict],
                                       solution: bool,
+                                      numbering: Numbering,
                                       hint_text: &HashMap<String, String>)
                                       -> Result<()> {
     let file = try!(File::create(filename));
     let mut writer = BufWriter::new(file);
-    html::write_html(&mut writer, cw, solution, hint_text)
+    html::write_html(&mut writer, cw, dicts, solution, numbering, hint_text)
 }
 
 /// Print the usage help message.
 fn print_usage(program: &str, opts: &Options) {
-    let brief = format!("Usage: {} [options]", program);
+    let brief = format!(concat!("Usage: {} [command] [options]\n\n",
+                                "Commands:\n",
+                                "    (none)              generate a crosswords grid\n",
+                                "    validate FILE...    check grids saved with --save\n",
+                                "    crisscross WORDLIST...\n",
+                                "                        interlock the given words as compactly\n",
+                                "                        as possible, without filler words\n",
+                                "    query PATTERN...    list the words matching a pattern like\n",
+                                "                        B#R[AEIOU]#, where # or ? is any letter\n",
+                                "    dict compile WORDLIST INDEXFILE\n",
+                                "                        write a precompiled index that can be\n",
+                                "                        used with --dict instead of the list\n",
+                                "    dict stats WORDLIST...\n",
+                                "                        print word lengths, letter frequencies\n",
+                                "                        and n-gram counts\n",
+                                "    dict filter WORDLIST OUTFILE\n",
+                                "                        write the words that pass the filters\n",
+                                "    dict merge OUTFILE WORDLIST...\n",
+                                "                        write the words of all lists; earlier\n",
+                                "                        lists take precedence\n",
+                                "    dict diff OLD NEW   list added, removed and rescored words\n",
+                                "    dict build OUTFILE CORPUS...\n",
+                                "                        write the words in the text files,\n",
+                                "                        scored by frequency"),
+                        program);
     print!("{}", opts.usage(&brief));
 }
 
-/// Score the crosswords grid according to how many borders and favorite words it contains.
+/// Score the crosswords grid according to how many borders and words it contains, and how much
+/// the scores of its words exceed the default score. Words from the initial grid that are not in
+/// any dictionary count with the default score.
 fn evaluate(cw: &Crosswords, author: &Author) -> i32 {
     let empty_borders = (cw.max_border_count() - cw.count_borders()) as i32;
     let words = cw.get_words();
     let word_count = words.len() as i32;
-    let word_category_count =
-        words
-            .into_iter()
-            .fold(0, |sum, word| sum + author.get_word_category(word).unwrap()) as i32;
-    empty_borders + word_count - 2 * word_category_count
+    let quality = words
+        .iter()
+        .map(|word| author.get_word_score(word).unwrap_or(DEFAULT_SCORE) - DEFAULT_SCORE)
+        .sum::<i32>() / 10;
+    empty_borders + word_count + quality
 }
 
 /// Print the crosswords grid and the word count.
@@ -74,13 +115,101 @@ fn print_cw(cw: &Crosswords, author: &Author) {
     println!("{}", cw);
 }
 
+/// A transformation of the finished grid, given with `--transform`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Transform {
+    Transpose,
+    Rotate,
+    Mirror(Dir),
+    Crop,
+    /// Add the given number of empty rows and columns on each side.
+    Pad(usize),
+}
+
+impl FromStr for Transform {
+    type Err = String;
+
+    fn from_str(name: &str) -> std::result::Result<Transform, String> {
+        match name {
+            "transpose" => Ok(Transform::Transpose),
+            "rotate" => Ok(Transform::Rotate),
+            "mirror_right" => Ok(Transform::Mirror(Dir::Right)),
+            "mirror_down" => Ok(Transform::Mirror(Dir::Down)),
+            "crop" => Ok(Transform::Crop),
+            _ if name.starts_with("pad:") => {
+                match name[4..].parse() {
+                    Ok(n) => Ok(Transform::Pad(n)),
+                    Err(_) => Err(format!("invalid padding: {}", name)),
+                }
+            }
+            _ => {
+                Err(format!("unknown transformation: {} (expected transpose, rotate, \
+                             mirror_right, mirror_down, crop or pad:<N>)",
+                            name))
+            }
+        }
+    }
+}
+
+impl Transform {
+    /// Returns the transformed grid.
+    fn apply(&self, cw: &Crosswords) -> Crosswords {
+        match *self {
+            Transform::Transpose => cw.transposed(),
+            Transform::Rotate => cw.rotated(),
+            Transform::Mirror(dir) => cw.mirrored(dir),
+            Transform::Crop => cw.cropped(),
+            Transform::Pad(n) => cw.padded(n, n, n, n),
+        }
+    }
+}
+
+/// Parse the transformations given on the command line, and exit with an error message if one of
+/// them is not valid. This is done before the search, so that a typo doesn't surface only after
+/// the grid is finished.
+fn get_transforms(matches: &Matches) -> Vec<Transform> {
+    matches
+        .opt_strs("transform")
+        .iter()
+        .map(|name| {
+                 name.parse().unwrap_or_else(|err| {
+                                                 println!("{}", err);
+                                                 process::exit(1);
+                                             })
+             })
+        .collect()
+}
+
+/// Apply the transformations to the grid in the given order.
+fn transform_all(cw: Crosswords, transforms: &[Transform]) -> Crosswords {
+    transforms.iter().fold(cw, |cw, transform| transform.apply(&cw))
+}
+
 /// Create the Options object containing the list of valid command line options.
 fn create_opts() -> Options {
     let mut opts = Options::new();
     opts.optopt("s",
                 "size",
-                "size of the crosswords grid",
+                "size of the crosswords grid, or auto to try out sizes and pick the best one",
+                "<Width>x<Height>");
+    opts.optopt("",
+                "min_size",
+                "with --size auto, the smallest size to try (default 4x4)",
                 "<Width>x<Height>");
+    opts.optopt("",
+                "max_size",
+                "with --size auto, the largest size to try (default 20x15)",
+                "<Width>x<Height>");
+    opts.optopt("",
+                "max_aspect_ratio",
+                "with --size auto, the maximum ratio of the longer to the shorter side \
+                 (default 2)",
+                "FLOAT");
+    opts.optopt("",
+                "time_limit",
+                "with --size auto, the number of seconds to spend on trying out sizes \
+                 (default 60)",
+                "SECONDS");
     opts.optopt("c",
                 "min_crossing",
                 "minimum number of words crossing any given word",
@@ -98,6 +227,51 @@ fn create_opts() -> Options {
                 "min_word_len",
                 "don't use words shorter than that",
                 "INTEGER");
+    opts.optopt("",
+                "max_word_len",
+                "don't use words longer than that",
+                "INTEGER");
+    opts.optopt("",
+                "min_score",
+                "don't use words with a lower score",
+                "INTEGER");
+    opts.optmulti("",
+                  "exclude",
+                  "don't use the words from the given word list",
+                  "FILENAME");
+    opts.optmulti("",
+                  "exclude_tag",
+                  "don't use entries with the given tags: offensive, trademark or obscure",
+                  "TAGS");
+    opts.optmulti("",
+                  "exclude_regex",
+                  "don't use words matching the given regular expression as a whole",
+                  "REGEX");
+    opts.optmulti("",
+                  "require_regex",
+                  "only use words matching the given regular expression as a whole",
+                  "REGEX");
+    opts.optopt("",
+                "min_count",
+                "with dict build, skip words occurring fewer times in the corpus (default 2)",
+                "INTEGER");
+    opts.optopt("",
+                "lang",
+                "the language of the word lists: de (default), en, fr, es, sv, da, no, tr or el",
+                "LANGUAGE");
+    opts.optflag("",
+                 "base_forms",
+                 "only use the base forms from Hunspell dictionaries, without affixes");
+    opts.optopt("",
+                "max_n",
+                "index n-grams of up to this many letters (default 3); higher values are faster \
+                 but need more memory",
+                "INTEGER");
+    opts.optopt("",
+                "max_gap",
+                "index pairs of letters with up to this many letters between them (default 1); 0 \
+                 disables pairs",
+                "INTEGER");
     opts.optopt("",
                 "samples",
                 "number of grids to create and select the best from",
@@ -110,46 +284,694 @@ fn create_opts() -> Options {
                 "max_attempts",
                 "the maximum number of words to try out in each position",
                 "INTEGER");
+    opts.optopt("",
+                "max_overlap",
+                "discard samples sharing more than this percentage of words with an earlier one",
+                "FLOAT");
+    opts.optmulti("",
+                  "transform",
+                  "transform the finished grid: transpose, rotate, mirror_right, mirror_down, \
+                   crop or pad:<N>",
+                  "TRANSFORM");
+    opts.optopt("",
+                "init",
+                "start from the grid in the given file, with optional slot constraints",
+                "FILENAME");
+    opts.optflag("",
+                 "avoid_related",
+                 "don't use words that share a stem with another word in the grid, or that \
+                  contain it or are contained in it");
+    opts.optopt("",
+                "lemmas",
+                "with --avoid_related, take the stems from the given lemma file",
+                "FILENAME");
+    opts.optmulti("",
+                  "variants",
+                  "use at most one word of each group of variant spellings in the given file",
+                  "FILENAME");
+    opts.optopt("",
+                "save",
+                "save the grid as a text file that can be checked with the validate command",
+                "FILENAME");
+    opts.optflag("",
+                 "anagram",
+                 "query anagrams of the given letters, where # or ? is a blank");
+    opts.optflag("",
+                 "sub_anagrams",
+                 "with --anagram, also list words that use only some of the letters");
+    opts.optopt("",
+                "order",
+                "how to list query results: grouped (default) by dictionary, or by score",
+                "ORDER");
+    opts.optopt("",
+                "numbering",
+                "how to label the clues: sequential (default), separate or coordinates",
+                "SCHEME");
     opts
 }
 
-/// Return a list of dictionaries read from the given filenames.
-fn get_dicts<T: Iterator<Item = String>>(filenames: T, min_word_len: usize) -> Vec<Dict> {
-    let mut existing_words = HashSet::new();
-    let mut to_dict = move |filename| {
+/// The options for reading and indexing the dictionaries.
+struct DictOptions {
+    /// Decides which entries are skipped.
+    filter: WordFilter,
+    /// The maximum length of the indexed n-grams.
+    max_n: usize,
+    /// The maximum number of letters between the two letters of an indexed pair.
+    max_gap: usize,
+    /// The language profile used to normalize the words in word lists.
+    lang: Lang,
+    /// Whether to skip the inflected forms in Hunspell dictionaries.
+    base_forms: bool,
+    /// Whether to print how many entries each filter removed.
+    verbose: bool,
+    /// Whether to order words with equal scores deterministically instead of shuffling them.
+    deterministic: bool,
+}
+
+impl DictOptions {
+    /// Return the options given on the command line.
+    fn new(matches: &Matches) -> DictOptions {
+        let lang = matches.opt_str("lang").map_or_else(Lang::default, |s| s.parse().unwrap());
+        let mut filter =
+            WordFilter::new(matches.opt_str("m").map_or(2, |s| s.parse().unwrap()),
+                            matches.opt_str("min_score").map_or(i32::MIN, |s| s.parse().unwrap()));
+        for filename in matches.opt_strs("exclude") {
+            filter.exclude_words(read_word_list(&filename, lang)
+                                     .unwrap()
+                                     .into_iter()
+                                     .map(|((word, _, _), _)| word));
+        }
+        for tags in matches.opt_strs("exclude_tag") {
+            for tag in parse_tags(&tags).unwrap() {
+                filter.exclude_tag(tag);
+            }
+        }
+        if let Some(max_word_len) = matches.opt_str("max_word_len") {
+            filter.set_max_word_len(max_word_len.parse().unwrap());
+        }
+        for pattern in matches.opt_strs("exclude_regex") {
+            filter.exclude_pattern(&pattern).unwrap();
+        }
+        for pattern in matches.opt_strs("require_regex") {
+            filter.require_pattern(&pattern).unwrap();
+        }
+        DictOptions {
+            filter: filter,
+            max_n: matches.opt_str("max_n").map_or(DEFAULT_MAX_N, |s| s.parse().unwrap()),
+            max_gap: matches.opt_str("max_gap").map_or(DEFAULT_MAX_GAP, |s| s.parse().unwrap()),
+            lang: lang,
+            base_forms: matches.opt_present("base_forms"),
+            verbose: matches.opt_present("v"),
+            deterministic: false,
+        }
+    }
+}
+
+/// A word list entry together with its tags.
+type TaggedEntry = (WordEntry, Vec<Tag>);
+
+/// Return the words, scores, display forms and tags from the word list with the given filename.
+fn read_word_list(filename: &str, lang: Lang) -> Result<Vec<TaggedEntry>> {
+    let mut entries = Vec::new();
+    for line in BufReader::new(try!(File::open(filename))).lines() {
+        entries.extend(Dict::parse_tagged_line(try!(line), lang));
+    }
+    Ok(entries)
+}
+
+/// Return the words and display forms of the Hunspell dictionary with the given filename.
+fn read_hunspell(filename: &str, options: &DictOptions) -> Vec<WordEntry> {
+    Dict::read_hunspell(filename, options.base_forms)
+        .unwrap()
+        .into_iter()
+        .filter_map(|word| Dict::parse_line(word, options.lang))
+        .collect()
+}
+
+/// Return a new dictionary with the given entries, indexed according to the options.
+fn new_dict<T>(entries: T, lang: Lang, options: &DictOptions) -> Dict
+    where T: IntoIterator<Item = WordEntry>
+{
+    if options.deterministic {
+        let mut rng = XorShiftRng::new_unseeded();
+        Dict::from_entries_with_rng(entries, options.max_n, options.max_gap, lang, &mut rng)
+    } else {
+        Dict::from_entries(entries, options.max_n, options.max_gap, lang)
+    }
+}
+
+/// Return a list of dictionaries read from the given filenames, which can be word lists, Hunspell
+/// `.dic` files with an `.aff` file next to them, or index files created with `dict compile`.
+/// Words that are filtered out by the options are skipped, and so are words that are contained in
+/// an earlier dictionary.
+fn get_dicts<T: Iterator<Item = String>>(filenames: T, options: &DictOptions) -> Vec<Dict> {
+    let filenames: Vec<String> = filenames.collect();
+    let dict_count = filenames.len();
+    let mut existing_words = HashMap::new();
+    let mut to_dict = move |(i, filename): (usize, String)| {
+        let mut rejections = RejectionCounts::new();
         let dict = {
-            let words =
-                BufReader::new(File::open(filename).unwrap())
-                    .lines()
-                    .filter_map(Result::ok)
-                    .filter_map(Dict::normalize_word)
-                    .filter(|word| word.len() >= min_word_len && !existing_words.contains(word));
-            Dict::new(words)
+            let check = |word: &[char], score: i32, tags: &[Tag]| {
+                options.filter
+                    .check(word, score, tags)
+                    .or_else(|| existing_words.get(word).map(|&j| Rejection::Duplicate(j)))
+            };
+            if Dict::is_index_file(&filename) {
+                if options.filter.excludes_tags() {
+                    println!("{}: Compiled indexes contain no tags, so --exclude_tag can't be \
+                              applied. Pass it to dict compile instead.",
+                             filename);
+                    process::exit(1);
+                }
+                let dict = Dict::read_index(&filename).unwrap();
+                let mut buffer = Vec::new();
+                for (word, score, _) in dict.all_entries() {
+                    buffer.clear();
+                    buffer.extend(word);
+                    if let Some(rejection) = check(&buffer, score, &[]) {
+                        *rejections.entry(rejection).or_insert(0) += 1;
+                    }
+                }
+                if dict.get_max_n() == options.max_n && dict.get_max_gap() == options.max_gap &&
+                   rejections.is_empty() {
+                    // Use the memory-mapped index as it is, without copying the words.
+                    dict
+                } else {
+                    let entries: Vec<_> = dict.all_entries()
+                        .map(|(word, score, display)| {
+                                 (word.to_vec(), score, display.map(str::to_owned))
+                             })
+                        .filter(|&(ref word, score, _)| check(word, score, &[]).is_none())
+                        .collect();
+                    new_dict(entries, dict.get_lang(), options)
+                }
+            } else {
+                let mut keep = |word: &[char], score: i32, tags: &[Tag]| {
+                    let rejection = check(word, score, tags);
+                    if let Some(rejection) = rejection {
+                        *rejections.entry(rejection).or_insert(0) += 1;
+                    }
+                    rejection.is_none()
+                };
+                let entries = if Dict::is_hunspell_file(&filename) {
+                        read_hunspell(&filename, options)
+                            .into_iter()
+                            .map(|entry| (entry, Vec::new()))
+                            .collect()
+                    } else {
+                        read_word_list(&filename, options.lang).unwrap()
+                    }
+                    .into_iter()
+                    .filter(|&((ref word, score, _), ref tags)| keep(word, score, tags))
+                    .map(|(entry, _)| entry);
+                new_dict(entries, options.lang, options)
+            }
         };
-        existing_words.extend(dict.all_words().cloned());
+        if options.verbose && !rejections.is_empty() {
+            print_rejections(i, &rejections);
+        }
+        // Only the later dictionaries need to check for duplicates.
+        if i + 1 < dict_count {
+            existing_words.extend(dict.all_words().map(|word| (word.to_vec(), i)));
+        }
         dict
     };
-    filenames.map(&mut to_dict).collect()
+    filenames.into_iter().enumerate().map(&mut to_dict).collect()
 }
 
-pub fn main() {
-    let args: Vec<String> = env::args().collect();
-    let program = args[0].clone();
-    let opts = create_opts();
-    let matches = opts.parse(&args[1..]).unwrap();
-    if matches.opt_present("h") {
-        print_usage(&program, &opts);
-        return;
+/// Print how many entries of a dictionary were removed by each filter.
+fn print_rejections(i: usize, rejections: &RejectionCounts) {
+    println!("Dictionary {}: {} entries removed: {}",
+             i + 1,
+             rejections.values().sum::<usize>(),
+             rejections
+                 .iter()
+                 .map(|(rejection, count)| format!("{} {}", count, rejection))
+                 .join(", "));
+}
+
+/// Read the dictionaries given on the command line, or the default ones.
+fn read_dicts(matches: &Matches) -> Vec<Dict> {
+    get_dicts(match matches.opt_count("d") {
+                      0 => vec!["dict/favorites.txt".to_owned(), "dict/dict.txt".to_owned()],
+                      _ => matches.opt_strs("d"),
+                  }
+                  .into_iter(),
+              &DictOptions::new(matches))
+}
+
+/// Print the size of each dictionary and its index.
+fn print_dict_stats(dicts: &[Dict]) {
+    for (i, dict) in dicts.iter().enumerate() {
+        println!("Dictionary {}: {} words, {} constraint lists for n-grams of up to {} letters \
+                  and pairs with gaps of up to {}, {:.1} MB",
+                 i + 1,
+                 dict.word_count(),
+                 dict.list_count(),
+                 dict.get_max_n(),
+                 dict.get_max_gap(),
+                 dict.memory_usage() as f64 / 1_000_000.);
+    }
+}
+
+/// Print the number of pattern lookups in each dictionary and the number of words they matched.
+fn print_lookup_stats(dicts: &[Dict]) {
+    for (i, dict) in dicts.iter().enumerate() {
+        let (lookups, matches) = dict.lookup_counts();
+        println!("Dictionary {}: {} lookups, {} matching words, {:.1} per lookup",
+                 i + 1,
+                 lookups,
+                 matches,
+                 matches as f64 / cmp::max(lookups, 1) as f64);
+    }
+}
+
+/// Print the length histogram, the letter frequencies and the number of distinct n-grams of the
+/// dictionary.
+fn print_word_stats(dict: &Dict) {
+    let mut stats = WordStats::new(dict.get_max_n(), dict.get_max_gap());
+    stats.add_dict(dict);
+    println!("  Lengths: {}",
+             stats.length_counts()
+                 .iter()
+                 .map(|(len, count)| format!("{}: {}", len, count))
+                 .join(", "));
+    let letter_counts = stats.letter_counts();
+    let total = cmp::max(1, letter_counts.values().sum::<usize>()) as f64;
+    let mut letters: Vec<_> = letter_counts.into_iter().collect();
+    letters.sort_by_key(|&(_, count)| cmp::Reverse(count));
+    println!("  Letters: {}",
+             letters.iter()
+                 .map(|&(c, count)| format!("{} {:.1}%", c, 100. * count as f64 / total))
+                 .join(", "));
+    let alphabet_len = dict.get_lang().alphabet().chars().count() as f64;
+    let ngram_counts = stats.distinct_ngram_counts();
+    println!("  Distinct n-grams: {}",
+             ngram_counts.iter()
+                 .enumerate()
+                 .map(|(i, &count)| {
+                          format!("{}: {} ({:.1}% of all)",
+                                  i + 1,
+                                  count,
+                                  100. * count as f64 / alphabet_len.powi(i as i32 + 1))
+                      })
+                 .join(", "));
+}
+
+/// Write the entries of the dictionaries to a word list in the format read by `Dict::parse_line`,
+/// with their display forms and scores, sorted by descending score and then alphabetically.
+fn write_word_list(filename: &str, dicts: &[Dict]) -> Result<()> {
+    write_entries(filename,
+                  dicts.iter()
+                      .flat_map(|dict| dict.all_entries())
+                      .map(|(word, score, display)| {
+                               (display.map_or_else(|| word.chars().collect(), str::to_owned),
+                                score)
+                           })
+                      .collect())
+}
+
+/// Write the entries, given as display forms and scores, to a word list, sorted by descending
+/// score and then alphabetically.
+fn write_entries(filename: &str, mut entries: Vec<(String, i32)>) -> Result<()> {
+    entries.sort_by(|&(ref w0, s0), &(ref w1, s1)| s1.cmp(&s0).then_with(|| w0.cmp(w1)));
+    let mut writer = BufWriter::new(try!(File::create(filename)));
+    for (word, score) in entries {
+        try!(writeln!(writer, "{};{}", word, score));
+    }
+    Ok(())
+}
+
+/// Count the words in the corpus files and write the ones that pass the filters to a word list,
+/// scored by their frequency.
+fn build_word_list(outfile: &str,
+                   corpus_files: &[String],
+                   options: &DictOptions,
+                   min_count: usize) {
+    let mut counts = WordCounts::new();
+    for filename in corpus_files {
+        if let Err(err) = counts.read_file(filename) {
+            println!("{}: {}", filename, err);
+            process::exit(1);
+        }
+    }
+    let all_entries = counts.entries(options.lang, min_count);
+    let mut rejections = RejectionCounts::new();
+    let mut entries = Vec::new();
+    for (word, score, display) in all_entries {
+        match options.filter.check(&word, score, &[]) {
+            Some(rejection) => *rejections.entry(rejection).or_insert(0) += 1,
+            None => entries.push((display.unwrap_or_else(|| word.iter().collect()), score)),
+        }
+    }
+    if !rejections.is_empty() {
+        print_rejections(0, &rejections);
+    }
+    println!("{} tokens, {} words occurring at least {} times",
+             counts.token_count(),
+             entries.len(),
+             min_count);
+    write_entries(outfile, entries).unwrap();
+}
+
+/// A difference between two dictionaries: the word, `+` if it was added, `-` if it was removed or
+/// `~` if it was rescored, and its score or its old and new score.
+type DictChange = (String, char, String);
+
+/// Return the words that are only in the old or only in the new dictionary, and the words whose
+/// score changed, in alphabetical order.
+fn dict_diff(old: &Dict, new: &Dict) -> Vec<DictChange> {
+    let mut changes = Vec::new();
+    for (word, score) in old.all_scored_words() {
+        let word = word.to_vec();
+        match new.get_score(&word) {
+            None => changes.push((word.into_iter().collect(), '-', score.to_string())),
+            Some(new_score) if new_score != score => {
+                changes.push((word.into_iter().collect(),
+                              '~',
+                              format!("{} -> {}", score, new_score)))
+            }
+            Some(_) => (),
+        }
+    }
+    for (word, score) in new.all_scored_words() {
+        let word = word.to_vec();
+        if !old.contains(&word) {
+            changes.push((word.into_iter().collect(), '+', score.to_string()));
+        }
     }
+    changes.sort();
+    changes
+}
+
+/// Print the words that are only in the old or only in the new dictionary, and the words whose
+/// score changed, in alphabetical order.
+fn print_dict_diff(old: &Dict, new: &Dict) {
+    let changes = dict_diff(old, new);
+    for &(ref word, sign, ref scores) in &changes {
+        println!("{} {} {}", sign, word, scores);
+    }
+    let count = |sign| changes.iter().filter(|change| change.1 == sign).count();
+    println!("{} added, {} removed, {} rescored", count('+'), count('-'), count('~'));
+}
+
+/// Run the `dict` command with the given arguments.
+fn dict_command(matches: &Matches, args: &[String]) {
+    let options = DictOptions {
+        verbose: true,
+        deterministic: args.first().map(String::as_str) == Some("compile"),
+        ..DictOptions::new(matches)
+    };
+    let read_dict = |filename: &String| get_dicts(iter::once(filename.clone()), &options).remove(0);
+    match args.first().map(|s| s.as_str()) {
+        Some("compile") if args.len() == 3 => {
+            let dicts = vec![read_dict(&args[1])];
+            let mut writer = BufWriter::new(File::create(&args[2]).unwrap());
+            dicts[0].write_index(&mut writer).unwrap();
+            print_dict_stats(&dicts);
+        }
+        Some("stats") if args.len() >= 2 => {
+            for filename in &args[1..] {
+                let dict = read_dict(filename);
+                println!("{}: {} words", filename, dict.word_count());
+                print_word_stats(&dict);
+            }
+        }
+        Some("filter") if args.len() == 3 => {
+            write_word_list(&args[2], &[read_dict(&args[1])]).unwrap();
+        }
+        Some("merge") if args.len() >= 3 => {
+            let dicts = get_dicts(args[2..].iter().cloned(), &options);
+            write_word_list(&args[1], &dicts).unwrap();
+            print_dict_stats(&dicts);
+        }
+        Some("diff") if args.len() == 3 => {
+            print_dict_diff(&read_dict(&args[1]), &read_dict(&args[2]));
+        }
+        Some("build") if args.len() >= 3 => {
+            let min_count = matches.opt_str("min_count").map_or(2, |s| s.parse().unwrap());
+            build_word_list(&args[1], &args[2..], &options, min_count);
+        }
+        _ => {
+            println!("Usage: dict compile WORDLIST INDEXFILE\n       \
+                      dict stats WORDLIST...\n       \
+                      dict filter WORDLIST OUTFILE\n       \
+                      dict merge OUTFILE WORDLIST...\n       \
+                      dict diff OLD NEW\n       \
+                      dict build OUTFILE CORPUS...");
+            process::exit(1);
+        }
+    }
+}
+
+/// Return the minimum absolute and relative numbers of crossing words given on the command line.
+fn get_min_crossing(matches: &Matches) -> (usize, usize) {
+    (matches.opt_str("c").map_or(2, |s| s.parse().unwrap()),
+     matches.opt_str("p").map_or(30, |s| s.parse().unwrap()))
+}
+
+/// Check the grids saved in the given files and print all violations. Exit with an error code if
+/// any were found.
+fn validate(matches: &Matches, filenames: &[String]) {
+    let dicts = read_dicts(matches);
+    let (min_crossing, min_crossing_percent) = get_min_crossing(matches);
+    let mut valid = true;
+    for filename in filenames {
+        let mut text = String::new();
+        File::open(filename).unwrap().read_to_string(&mut text).unwrap();
+        let violations = match Crosswords::from_text(&text) {
+            Ok(cw) => cw.validate(&dicts, min_crossing, min_crossing_percent),
+            Err(err) => {
+                println!("{}: {}", filename, err);
+                valid = false;
+                continue;
+            }
+        };
+        for violation in &violations {
+            println!("{}: {}", filename, violation);
+        }
+        println!("{}: {} violations", filename, violations.len());
+        valid = valid && violations.is_empty();
+    }
+    if !valid {
+        process::exit(1);
+    }
+}
+
+/// Print a word matching a query, with its score and display form.
+fn print_match(dict: &Dict, word: &[char], score: i32, category: Option<usize>) {
+    let display = dict.get_display_form(word).map_or_else(String::new, |s| format!(" ({})", s));
+    let category = category.map_or_else(String::new, |i| format!(" [{}]", i + 1));
+    println!("    {} {}{}{}",
+             word.iter().collect::<String>(),
+             score,
+             display,
+             category);
+}
+
+/// Print the words from the dictionaries matching each of the given queries, together with the
+/// number of matches that `WordStats` estimates for them. With `--anagram`, print the anagrams of
+/// each query's letters instead.
+fn query(matches: &Matches, queries: &[String]) {
+    let dicts = read_dicts(matches);
+    let anagram = matches.opt_present("anagram");
+    let sub_anagrams = matches.opt_present("sub_anagrams");
+    let lang = dicts.first().map_or_else(Lang::default, Dict::get_lang);
+    let order = matches
+        .opt_str("order")
+        .map_or(QueryOrder::Grouped, |s| s.parse().unwrap());
+    let max_n = dicts.iter().map(Dict::get_max_n).min().unwrap_or(DEFAULT_MAX_N);
+    let max_gap = dicts.iter().map(Dict::get_max_gap).min().unwrap_or(DEFAULT_MAX_GAP);
+    let mut total_stats = WordStats::new(max_n, max_gap);
+    let stats: Vec<WordStats> = dicts
+        .iter()
+        .map(|dict| {
+                 total_stats.add_dict(dict);
+                 let mut stats = WordStats::new(dict.get_max_n(), dict.get_max_gap());
+                 stats.add_dict(dict);
+                 stats
+             })
+        .collect();
+    for query in queries {
+        let query = Query::parse(query, lang).unwrap_or_else(|err| {
+                                                                 println!("{}", err);
+                                                                 process::exit(1);
+                                                             });
+        let (pattern, classes) = (query.get_pattern(), query.get_classes());
+        if anagram && !classes.is_empty() {
+            println!("Letter classes are not supported in anagram queries: {}", query);
+            process::exit(1);
+        }
+        let estimate = |stats: &WordStats| if anagram {
+            String::new()
+        } else {
+            format!(", estimated {:.1}", stats.estimate_class_matches(pattern, classes))
+        };
+        let results: Vec<Vec<(Vec<char>, i32)>> = dicts
+            .iter()
+            .map(|dict| {
+                let mut iter = if anagram {
+                    dict.anagrams(pattern, sub_anagrams)
+                } else {
+                    dict.matching_words(pattern)
+                };
+                let mut result = Vec::new();
+                while let Some(score) = iter.peek_score() {
+                    let word = iter.next().unwrap().to_vec();
+                    if query.matches_classes(&word) {
+                        result.push((word, score));
+                    }
+                }
+                result
+            })
+            .collect();
+        println!("{}: {} matches{}",
+                 query,
+                 results.iter().map(Vec::len).sum::<usize>(),
+                 estimate(&total_stats));
+        match order {
+            QueryOrder::Grouped => {
+                for (i, result) in results.iter().enumerate() {
+                    println!("  Dictionary {}: {} matches{}",
+                             i + 1,
+                             result.len(),
+                             estimate(&stats[i]));
+                    for &(ref word, score) in result {
+                        print_match(&dicts[i], word, score, None);
+                    }
+                }
+            }
+            QueryOrder::Score => {
+                let mut all: Vec<_> = results
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, result)| result.iter().map(move |entry| (i, entry)))
+                    .collect();
+                all.sort_by_key(|&(_, &(_, score))| -score);
+                for (i, &(ref word, score)) in all {
+                    print_match(&dicts[i], word, score, Some(i));
+                }
+            }
+        }
+    }
+}
+
+/// Read the file given with `--init`: It can start with a grid in the format written by `--save`,
+/// which generation starts from, followed by an empty line and constraints for the words in
+/// certain slots, in the format accepted by `SlotConstraints::parse`.
+fn read_init_file(filename: &str, lang: Lang) -> (Option<Crosswords>, SlotConstraints) {
+    let mut text = String::new();
+    File::open(filename).unwrap().read_to_string(&mut text).unwrap();
+    let exit_with = |err: String| -> ! {
+        println!("{}: {}", filename, err);
+        process::exit(1);
+    };
+    let text = text.trim_start();
+    let (cw, slots_text) = if text.starts_with('+') {
+        let grid_len = text.find("\n\n").or_else(|| text.find("\n\r\n")).unwrap_or(text.len());
+        let cw = Crosswords::from_text(&text[..grid_len]).unwrap_or_else(|err| exit_with(err));
+        (Some(cw), &text[grid_len..])
+    } else {
+        (None, text)
+    };
+    let base_dir = Path::new(filename).parent().unwrap_or_else(|| Path::new(""));
+    let slots = SlotConstraints::parse(slots_text, lang, base_dir)
+        .unwrap_or_else(|err| exit_with(err));
+    (cw, slots)
+}
+
+/// Parse a grid size in the format `<Width>x<Height>`.
+fn parse_size(s: &str) -> (usize, usize) {
+    let size: Vec<usize> = s.split('x').map(|s| s.parse().unwrap()).collect();
+    (size[0], size[1])
+}
+
+/// The minimum number of words with at most the width or height of a grid per cell, below which
+/// a grid size is considered infeasible.
+const MIN_WORDS_PER_CELL: f32 = 2.;
+
+/// Try out the grid sizes within the bounds given on the command line, largest first, and return
+/// the grid with the best score, together with the author that completed it. Sizes whose width and
+/// height are both at most those of a size that was already filled are skipped, and so are sizes
+/// for which the dictionaries contain too few words that fit. Each size gets an equal share of the
+/// remaining time limit.
+fn choose_size<'a, F>(matches: &Matches, dicts: &[Dict], new_author: F) -> (Crosswords, Author<'a>)
+    where F: Fn(&Crosswords) -> Author<'a>
+{
+    let size_opt = |name, default| parse_size(&matches.opt_str(name).unwrap_or(default));
+    let (min_width, min_height) = size_opt("min_size", "4x4".to_owned());
+    let (max_width, max_height) = size_opt("max_size", "20x15".to_owned());
+    let max_ratio = matches.opt_str("max_aspect_ratio").map_or(2., |s| s.parse().unwrap());
+    let time_limit = matches.opt_str("time_limit").map_or(60, |s| s.parse().unwrap());
+    let verbose = matches.opt_present("v");
+    let mut stats = WordStats::new(1, 0);
+    for dict in dicts {
+        stats.add_dict(dict);
+    }
+    let mut sizes: Vec<(usize, usize)> = (min_width..(max_width + 1))
+        .cartesian_product(min_height..(max_height + 1))
+        .filter(|&(w, h)| cmp::max(w, h) as f32 <= max_ratio * cmp::min(w, h) as f32)
+        .filter(|&(w, h)| {
+                    (stats.count_up_to(w) + stats.count_up_to(h)) as f32 >=
+                    MIN_WORDS_PER_CELL * (w * h) as f32
+                })
+        .collect();
+    sizes.sort_by_key(|&(w, h)| (cmp::Reverse(w * h), (w as i32 - h as i32).abs()));
+    let end = Instant::now() + Duration::from_secs(time_limit);
+    let mut best: Option<(Crosswords, Author<'a>, i32)> = None;
+    let mut filled: Vec<(usize, usize)> = Vec::new();
+    for (i, &(width, height)) in sizes.iter().enumerate() {
+        let now = Instant::now();
+        if now >= end {
+            break;
+        }
+        if filled.iter().any(|&(w, h)| width <= w && height <= h) {
+            continue;
+        }
+        let deadline = now + (end - now) / (sizes.len() - i) as u32;
+        let mut author = new_author(&Crosswords::new(width, height)).with_deadline(deadline);
+        let cw = match author.complete_cw() {
+            Some(cw) => cw,
+            None => {
+                if verbose {
+                    println!("{}x{}: no grid found", width, height);
+                }
+                continue;
+            }
+        };
+        let score = evaluate(&cw, &author);
+        if verbose {
+            println!("{}x{}: score {}", width, height, score);
+        }
+        filled.push((width, height));
+        let is_best = match best {
+            Some((_, _, best_score)) => score > best_score,
+            None => true,
+        };
+        if is_best {
+            best = Some((cw, author, score));
+        }
+    }
+    match best {
+        Some((cw, author, _)) => {
+            println!("Selected size {}x{}.", cw.get_width(), cw.get_height());
+            (cw, author)
+        }
+        None => {
+            println!("No grid size between {}x{} and {}x{} could be filled in time.",
+                     min_width,
+                     min_height,
+                     max_width,
+                     max_height);
+            process::exit(1);
+        }
+    }
+}
+
+/// Generate a crosswords grid and write it to HTML files.
+fn generate(matches: &Matches) {
     // TODO: Sanity checks for option values; proper error messages.
-    let size: Vec<usize> = matches
-        .opt_str("s")
-        .map_or(vec![15, 10],
-                |s| s.split('x').map(|s| s.parse().unwrap()).collect());
-    let (width, height): (usize, usize) = (size[0], size[1]);
-    let min_crossing = matches.opt_str("c").map_or(2, |s| s.parse().unwrap());
-    let min_crossing_percent = matches.opt_str("p").map_or(30, |s| s.parse().unwrap());
-    let min_word_len = matches.opt_str("m").map_or(2, |s| s.parse().unwrap());
+    let (min_crossing, min_crossing_percent) = get_min_crossing(matches);
     let max_attempts = matches
         .opt_str("max_attempts")
         .map_or(usize::MAX, |s| s.parse().unwrap());
@@ -157,47 +979,253 @@ pub fn main() {
         .opt_str("samples")
         .map_or(1, |s| s.parse().unwrap());
     let verbose = matches.opt_present("v");
-    let dicts =
-        get_dicts(match matches.opt_count("d") {
-                          0 => vec!["dict/favorites.txt".to_owned(), "dict/dict.txt".to_owned()],
-                          _ => matches.opt_strs("d"),
-                      }
-                      .into_iter(),
-                  min_word_len);
-    let mut author = Author::new(&Crosswords::new(width, height), &dicts)
-        .with_min_crossing(min_crossing, min_crossing_percent)
+    let transforms = get_transforms(matches);
+    let dicts = read_dicts(matches);
+    if verbose {
+        print_dict_stats(&dicts);
+    }
+    let lang = dicts.first().map_or_else(Lang::default, Dict::get_lang);
+    let (init_cw, slots) = match matches.opt_str("init") {
+        Some(filename) => read_init_file(&filename, lang),
+        None => (None, SlotConstraints::new()),
+    };
+    let mut stemmer = Stemmer::new(lang);
+    if let Some(filename) = matches.opt_str("lemmas") {
+        stemmer.read_lemmas(filename).unwrap();
+    }
+    let mut variants = VariantGroups::new(lang);
+    for filename in matches.opt_strs("variants") {
+        variants.read_file(filename).unwrap();
+    }
+    let new_author = |cw: &Crosswords| {
+        let mut author = Author::new(cw, &dicts)
+            .with_slot_constraints(&slots)
+            .with_min_crossing(min_crossing, min_crossing_percent)
+            .with_max_attempts(max_attempts);
+        if matches.opt_present("avoid_related") {
+            author = author.with_stemmer(&stemmer);
+        }
+        if matches.opt_present("variants") {
+            author = author.with_variants(&variants);
+        }
+        author
+    };
+    // With `--size auto`, the grid that determined the size is the first sample, and the search
+    // that found it continues for the others.
+    let (author, mut first_cw) = match (init_cw, matches.opt_str("s")) {
+        (Some(cw), _) => (new_author(&cw), None),
+        (None, Some(ref size)) if size == "auto" => {
+            let (cw, author) = choose_size(matches, &dicts, new_author);
+            (author.without_deadline(), Some(cw))
+        }
+        (None, size) => {
+            let (width, height) = parse_size(size.as_ref().map_or("15x10", |s| s));
+            (new_author(&Crosswords::new(width, height)), None)
+        }
+    };
+    let mut solutions = author
         .with_verbosity(verbose)
-        .with_max_attempts(max_attempts);
+        .solutions()
+        .distinct()
+        .backtrack_to(1)
+        .limit(samples);
+    if let Some(ref cw) = first_cw {
+        solutions = solutions.after(cw);
+    }
+    let max_overlap = matches
+        .opt_str("max_overlap")
+        .map_or(100., |s| s.parse::<f32>().unwrap());
     let (mut best_cw, mut best_val) = (None, i32::MIN);
-    for i in 0..samples {
-        if let Some(cw) = author.complete_cw() {
-            let val = evaluate(&cw, &author);
-            if samples > 1 {
-                println!("Solution {} of {}:", i + 1, samples);
-                print_cw(&cw, &author);
-            }
-            if val > best_val {
-                best_cw = Some(cw);
-                best_val = val;
-            }
-            author.pop_to_n_words(1);
+    let mut sample_cws: Vec<Crosswords> = Vec::new();
+    let mut i = 0;
+    while let Some(cw) = first_cw.take().or_else(|| solutions.next()) {
+        i += 1;
+        if sample_cws
+               .iter()
+               .any(|other| 100. * cw.word_overlap(other) > max_overlap) {
+            println!("Solution {} is a near-duplicate.", i);
+            continue;
+        }
+        let val = evaluate(&cw, solutions.author());
+        if samples > 1 {
+            println!("Solution {}:", i);
+            print_cw(&cw, solutions.author());
         }
+        if val > best_val {
+            best_cw = Some(cw.clone());
+            best_val = val;
+        }
+        sample_cws.push(cw);
+    }
+    if verbose {
+        print_lookup_stats(&dicts);
     }
     if let Some(cw) = best_cw {
+        let cw = transform_all(cw, &transforms);
         if samples > 1 {
             println!("Best candidate:");
         }
-        print_cw(&cw, &author);
-        let hint_text = match matches.opt_str("wikipedia") {
-            None => HashMap::new(),
-            Some(lang) => {
-                let word_iter = cw.get_words()
-                    .iter()
-                    .map(|cvec| cvec.iter().cloned().collect());
-                get_hints(word_iter, &lang)
-            }
+        print_cw(&cw, solutions.author());
+        write_output(matches, &cw, &dicts);
+    }
+}
+
+/// Write the puzzle and the solution to HTML files, with hints if requested, and save the grid
+/// if requested.
+fn write_output(matches: &Matches, cw: &Crosswords, dicts: &[Dict]) {
+    let numbering = matches
+        .opt_str("numbering")
+        .map_or(Numbering::Sequential, |s| s.parse().unwrap());
+    let hint_text = match matches.opt_str("wikipedia") {
+        None => HashMap::new(),
+        Some(lang) => {
+            let word_iter = cw.entries(dicts, numbering)
+                .into_iter()
+                .map(|entry| entry.display);
+            get_hints(word_iter, &lang)
+        }
+    };
+    write_html_to_file("puzzle.html", cw, dicts, false, numbering, &hint_text).unwrap();
+    write_html_to_file("solution.html", cw, dicts, true, numbering, &hint_text).unwrap();
+    if let Some(filename) = matches.opt_str("save") {
+        File::create(filename)
+            .unwrap()
+            .write_all(cw.to_text().as_bytes())
+            .unwrap();
+    }
+}
+
+/// Arrange the words from the given word lists in a compact criss-cross grid without filler words
+/// and write it to HTML files.
+fn criss_cross(matches: &Matches, filenames: &[String]) {
+    if filenames.is_empty() {
+        println!("Usage: crisscross WORDLIST...");
+        process::exit(1);
+    }
+    let transforms = get_transforms(matches);
+    let dicts = get_dicts(filenames.iter().cloned(), &DictOptions::new(matches));
+    let samples = matches.opt_str("samples").map_or(100, |s| s.parse().unwrap());
+    let words = dicts.iter().flat_map(|dict| dict.all_words()).map(|word| word.to_vec());
+    let layout = match CrissCross::new(words).layout(samples) {
+        Some(layout) => layout,
+        None => {
+            println!("No words to arrange.");
+            process::exit(1);
+        }
+    };
+    let cw = transform_all(layout.cw, &transforms);
+    println!("{} words, {} crossings, {} x {} cells",
+             cw.get_words().len(),
+             layout.crossings,
+             cw.get_width(),
+             cw.get_height());
+    if !layout.unplaced.is_empty() {
+        println!("Could not place: {}",
+                 layout.unplaced.iter().map(|word| word.iter().collect::<String>()).join(", "));
+    }
+    println!("{}", cw);
+    write_output(matches, &cw, &dicts);
+}
+
+pub fn main() {
+    let args: Vec<String> = env::args().collect();
+    let program = args[0].clone();
+    let opts = create_opts();
+    let matches = opts.parse(&args[1..]).unwrap();
+    if matches.opt_present("h") {
+        print_usage(&program, &opts);
+        return;
+    }
+    match matches.free.first().map(|s| s.as_str()) {
+        None => generate(&matches),
+        Some("validate") => validate(&matches, &matches.free[1..]),
+        Some("dict") => dict_command(&matches, &matches.free[1..]),
+        Some("query") => query(&matches, &matches.free[1..]),
+        Some("crisscross") => criss_cross(&matches, &matches.free[1..]),
+        Some(command) => {
+            println!("Unknown command: {}", command);
+            print_usage(&program, &opts);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use test_util::*;
+
+    fn default_options() -> DictOptions {
+        DictOptions::new(&create_opts().parse(Vec::<String>::new()).unwrap())
+    }
+
+    #[test]
+    fn test_merge() {
+        let path = |name: &str| {
+            let name = format!("crosswords-rs-test-{}-{}", process::id(), name);
+            env::temp_dir().join(name).to_str().unwrap().to_owned()
         };
-        write_html_to_file("puzzle.html", &cw, false, &hint_text).unwrap();
-        write_html_to_file("solution.html", &cw, true, &hint_text).unwrap();
+        let (first, second, merged) = (path("first.txt"), path("second.txt"), path("merged.txt"));
+        fs::write(&first, "FOO;10\nBAR;20\n").unwrap();
+        fs::write(&second, "FOO;90\nBAZ;30\n").unwrap();
+        let dicts = get_dicts(vec![first.clone(), second.clone()].into_iter(), &default_options());
+        write_word_list(&merged, &dicts).unwrap();
+        let text = fs::read_to_string(&merged).unwrap();
+        for filename in &[first, second, merged] {
+            fs::remove_file(filename).unwrap();
+        }
+        // The entry from the earlier list wins.
+        assert_eq!(Some(10), dicts[0].get_score(&str_to_cvec("FOO")));
+        assert!(!dicts[1].contains(&str_to_cvec("FOO")));
+        assert_eq!("BAZ;30\nBAR;20\nFOO;10\n", text);
+    }
+
+    #[test]
+    fn test_dict_diff() {
+        let old = scored_dict(vec![(str_to_cvec("FOO"), 10),
+                                   (str_to_cvec("BAR"), 20),
+                                   (str_to_cvec("BAZ"), 30)]);
+        let new = scored_dict(vec![(str_to_cvec("FOO"), 10),
+                                   (str_to_cvec("BAR"), 25),
+                                   (str_to_cvec("QUX"), 40)]);
+        let change = |word: &str, sign, scores: &str| (word.to_owned(), sign, scores.to_owned());
+        assert_eq!(vec![change("BAR", '~', "20 -> 25"),
+                        change("BAZ", '-', "30"),
+                        change("QUX", '+', "40")],
+                   dict_diff(&old, &new));
+        assert!(dict_diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_choose_size() {
+        let args = ["--min_size", "3x3", "--max_size", "5x4", "--time_limit", "1"];
+        let matches = create_opts().parse(&args).unwrap();
+        // All words with two or three of the letters A, B and C.
+        let mut words = Vec::new();
+        for (a, b) in "ABC".chars().cartesian_product("ABC".chars()) {
+            words.push(vec![a, b]);
+            words.extend("ABC".chars().map(|c| vec![a, b, c]));
+        }
+        let dicts = vec![words_to_dict(words)];
+        let (cw, author) = choose_size(&matches, &dicts, |cw| Author::new(cw, &dicts));
+        assert!((3..6).contains(&cw.get_width()) && (3..5).contains(&cw.get_height()));
+        assert!(cw.is_full());
+        // The author resumes the search that found the grid.
+        let next_cw = author.without_deadline().complete_cw().unwrap();
+        assert_eq!((cw.get_width(), cw.get_height()),
+                   (next_cw.get_width(), next_cw.get_height()));
+        assert!(cw != next_cw);
+    }
+
+    #[test]
+    fn test_transform() {
+        let cw = Crosswords::new(3, 2);
+        let transform = |name: &str| name.parse::<Transform>().map(|t| t.apply(&cw));
+        assert_eq!(Ok(Transform::Pad(1)), "pad:1".parse());
+        assert_eq!(5, transform("pad:1").unwrap().get_width());
+        assert_eq!(2, transform("transpose").unwrap().get_width());
+        assert!(transform("pad:x").is_err());
+        assert!(transform("flip").is_err());
     }
 }
@@ -0,0 +1,134 @@
+use cw::BLOCK;
+use dict::Lang;
+use std::fmt;
+use std::str::FromStr;
+
+/// A pattern that a constructor can look up in the dictionaries, e. g. `B#R[AEIOU]#`: Letters
+/// must match exactly, `#` and `?` match any letter, and a class in brackets matches any of the
+/// letters in it, or, if it starts with `^`, any letter that is not in it.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Query {
+    /// The pattern as used by `Dict::matching_words`: classes are `BLOCK`s.
+    pattern: Vec<char>,
+    /// The positions of the letter classes, together with the letters they allow.
+    classes: Vec<(usize, Vec<char>)>,
+}
+
+impl Query {
+    /// Parses the query, normalizing its letters according to the language.
+    pub fn parse(s: &str, lang: Lang) -> Result<Query, String> {
+        let normalize = |c: char| {
+            lang.normalize(&c.to_string())
+                .ok_or_else(|| format!("invalid letter in query: {}", c))
+        };
+        let mut query = Query {
+            pattern: Vec::new(),
+            classes: Vec::new(),
+        };
+        let mut chars = s.trim().chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '#' | '?' => query.pattern.push(BLOCK),
+                '[' => {
+                    let class: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                    let (negated, class) = match class.strip_prefix('^') {
+                        Some(rest) => (true, rest),
+                        None => (false, &class[..]),
+                    };
+                    let mut letters = Vec::new();
+                    for c in class.chars() {
+                        match normalize(c)?[..] {
+                            [letter] => letters.push(letter),
+                            _ => return Err(format!("not a single letter in class: {}", c)),
+                        }
+                    }
+                    if negated {
+                        letters = lang.alphabet()
+                            .chars()
+                            .filter(|c| !letters.contains(c))
+                            .collect();
+                    }
+                    query.classes.push((query.pattern.len(), letters));
+                    query.pattern.push(BLOCK);
+                }
+                c => query.pattern.extend(normalize(c)?),
+            }
+        }
+        if query.pattern.is_empty() {
+            return Err("empty query".to_owned());
+        }
+        Ok(query)
+    }
+
+    /// Returns the pattern to look up in the dictionaries: the letters, with wildcards and
+    /// classes as `BLOCK`s.
+    pub fn get_pattern(&self) -> &[char] {
+        &self.pattern
+    }
+
+    /// Returns the positions of the letter classes and the letters they allow.
+    pub fn get_classes(&self) -> &[(usize, Vec<char>)] {
+        &self.classes
+    }
+
+    /// Returns whether a word matching the pattern also satisfies the letter classes.
+    pub fn matches_classes(&self, word: &[char]) -> bool {
+        self.classes.iter().all(|&(pos, ref letters)| letters.contains(&word[pos]))
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, &c) in self.pattern.iter().enumerate() {
+            match self.classes.iter().find(|class| class.0 == i) {
+                Some((_, letters)) => {
+                    write!(f, "[{}]", letters.iter().collect::<String>())?
+                }
+                None => write!(f, "{}", c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How the results of a query are listed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QueryOrder {
+    /// The matches of each dictionary, i. e. each word category, separately.
+    Grouped,
+    /// The matches of all dictionaries together, sorted by descending score.
+    Score,
+}
+
+impl FromStr for QueryOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<QueryOrder, String> {
+        match s {
+            "grouped" => Ok(QueryOrder::Grouped),
+            "score" => Ok(QueryOrder::Score),
+            _ => Err(format!("unknown query order: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::*;
+
+    #[test]
+    fn test_parse() {
+        let query = Query::parse("b?r[aeiou]#", Lang::English).unwrap();
+        assert_eq!(str_to_cvec("B#R##"), query.get_pattern());
+        assert_eq!("B#R[AEIOU]#", query.to_string());
+        assert!(query.matches_classes(&str_to_cvec("BARON")));
+        assert!(!query.matches_classes(&str_to_cvec("BURNT")));
+        let query = Query::parse("ö[^aeiou]", Lang::German).unwrap();
+        assert_eq!(str_to_cvec("OE#"), query.get_pattern());
+        assert_eq!(21, query.get_classes()[0].1.len());
+        assert!(Query::parse("a[ä]", Lang::German).is_err());
+        assert!(Query::parse("", Lang::German).is_err());
+        assert!(Query::parse("a!", Lang::German).is_err());
+    }
+}
@@ -1,5 +1,7 @@
 #![cfg(test)]
 
+use dict::{DEFAULT_MAX_GAP, DEFAULT_MAX_N, DEFAULT_SCORE, Dict, Lang};
+
 /// Converts a `str` to a `Vec<char>`.
 pub fn str_to_cvec<T: AsRef<str>>(s: T) -> Vec<char> {
     s.as_ref().chars().collect()
@@ -9,3 +11,14 @@ pub fn str_to_cvec<T: AsRef<str>>(s: T) -> Vec<char> {
 pub fn strs_to_cvecs(strs: &[&str]) -> Vec<Vec<char>> {
     strs.into_iter().map(str_to_cvec).collect()
 }
+
+/// Creates a `Dict` containing the given words with their scores.
+pub fn scored_dict<T: IntoIterator<Item = (Vec<char>, i32)>>(scored_words: T) -> Dict {
+    let entries = scored_words.into_iter().map(|(word, score)| (word, score, None));
+    Dict::from_entries(entries, DEFAULT_MAX_N, DEFAULT_MAX_GAP, Lang::default())
+}
+
+/// Creates a `Dict` containing the given words, all with the default score.
+pub fn words_to_dict<T: IntoIterator<Item = Vec<char>>>(words: T) -> Dict {
+    scored_dict(words.into_iter().map(|word| (word, DEFAULT_SCORE)))
+}
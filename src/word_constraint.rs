@@ -4,8 +4,8 @@ use std::ops;
 use std::option;
 
 /// A `WordConstraint` represents the subset of all words with a given length, and optionally with
-/// a given n-gram at a specific position.
-#[derive(Clone, Hash, Eq, PartialEq)]
+/// a given n-gram at a specific position or a given pair of letters at two non-adjacent positions.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub enum WordConstraint {
     /// All words with the given length.
     Length(usize),
@@ -18,6 +18,36 @@ pub enum WordConstraint {
     /// Words with the given n-gram at the specified position. For n <= 3, the corresponding more
     /// specific variant should be used to avoid ambiguity and heap-allocating the n-gram.
     NGramAt(Vec<char>, usize, usize),
+    /// Words with the given two characters at the first and second specified position,
+    /// respectively. The positions are not adjacent, and the first one is less than the second.
+    PairAt([char; 2], usize, usize, usize),
+}
+
+/// An iterator over all constraints specifying pairs of non-adjacent letters in a given word,
+/// that are separated by at most `max_gap` other letters.
+pub struct PairIter<'a> {
+    word: &'a [char],
+    max_gap: usize,
+    pos0: usize,
+    pos1: usize,
+}
+
+impl<'a> Iterator for PairIter<'a> {
+    type Item = WordConstraint;
+
+    fn next(&mut self) -> Option<WordConstraint> {
+        if self.pos1 >= self.word.len() || self.pos1 > self.pos0 + self.max_gap + 1 {
+            self.pos0 += 1;
+            self.pos1 = self.pos0 + 2;
+        }
+        if self.max_gap == 0 || self.pos1 >= self.word.len() {
+            return None;
+        }
+        let (c0, c1) = (self.word[self.pos0], self.word[self.pos1]);
+        let wc = WordConstraint::PairAt([c0, c1], self.pos0, self.pos1, self.word.len());
+        self.pos1 += 1;
+        Some(wc)
+    }
 }
 
 type NgramIter<'a> = iter::Map<iter::Zip<slice::Windows<'a, char>,
@@ -29,7 +59,8 @@ type AllNgramIter<'a> = iter::FlatMap<iter::Zip<iter::Repeat<&'a [char]>, ops::R
                                       fn((&'a [char], usize)) -> NgramIter<'a>>;
 
 /// An iterator over all constraints applying to a given word.
-pub type AllConstraintsIter<'a> = iter::Chain<AllNgramIter<'a>, option::IntoIter<WordConstraint>>;
+pub type AllConstraintsIter<'a> = iter::Chain<iter::Chain<AllNgramIter<'a>, PairIter<'a>>,
+                                              option::IntoIter<WordConstraint>>;
 
 impl WordConstraint {
     /// Create a `WordConstraint` that specifies all words of the given length, and, if the given
@@ -44,6 +75,31 @@ impl WordConstraint {
         }
     }
 
+    /// Return the length of the n-gram, 2 for a pair of letters or 0 for a `Length` constraint.
+    pub fn ngram_len(&self) -> usize {
+        match *self {
+            WordConstraint::Length(_) => 0,
+            WordConstraint::CharAt(..) => 1,
+            WordConstraint::BigramAt(..) => 2,
+            WordConstraint::TrigramAt(..) => 3,
+            WordConstraint::NGramAt(ref ngram, _, _) => ngram.len(),
+            WordConstraint::PairAt(..) => 2,
+        }
+    }
+
+    /// Return the n-gram of a constraint that specifies one at a position, or `None` for `Length`
+    /// and `PairAt` constraints.
+    pub fn ngram(&self) -> Option<&[char]> {
+        match *self {
+            WordConstraint::CharAt(ref c, _, _) => Some(slice::from_ref(c)),
+            WordConstraint::BigramAt(ref ngram, _, _) => Some(&ngram[..]),
+            WordConstraint::TrigramAt(ref ngram, _, _) => Some(&ngram[..]),
+            WordConstraint::NGramAt(ref ngram, _, _) => Some(&ngram[..]),
+            WordConstraint::Length(_) |
+            WordConstraint::PairAt(..) => None,
+        }
+    }
+
     fn ngram_constraints(word: &[char], n: usize) -> NgramIter {
         fn to_constraint((ngram, (pos, len)): (&[char], (usize, usize))) -> WordConstraint {
             WordConstraint::with_ngram(ngram, pos, len)
@@ -60,9 +116,47 @@ impl WordConstraint {
         iter::repeat(word).zip(1..(max_n + 1)).flat_map(to_iter)
     }
 
-    /// Return an iterator over all constraints applying to a given word.
-    pub fn all(word: &[char], max_n: usize) -> AllConstraintsIter {
+    /// Return an iterator over all constraints specifying pairs of non-adjacent letters in the
+    /// given word, with at most `max_gap` letters between them.
+    pub fn pairs(word: &[char], max_gap: usize) -> PairIter<'_> {
+        PairIter {
+            word: word,
+            max_gap: max_gap,
+            pos0: 0,
+            pos1: 2,
+        }
+    }
+
+    /// Return an iterator over all constraints applying to a given word: its length, its n-grams
+    /// for n up to `max_n`, and its pairs of letters with between 1 and `max_gap` letters between
+    /// them.
+    pub fn all(word: &[char], max_n: usize, max_gap: usize) -> AllConstraintsIter {
         WordConstraint::all_ngram_constraints(word, max_n)
+            .chain(WordConstraint::pairs(word, max_gap))
             .chain(Some(WordConstraint::Length(word.len())))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::str_to_cvec;
+
+    #[test]
+    fn test_pairs() {
+        let word = str_to_cvec("ABCD");
+        let pairs: Vec<_> = WordConstraint::pairs(&word, 2).collect();
+        assert_eq!(vec![WordConstraint::PairAt(['A', 'C'], 0, 2, 4),
+                        WordConstraint::PairAt(['A', 'D'], 0, 3, 4),
+                        WordConstraint::PairAt(['B', 'D'], 1, 3, 4)],
+                   pairs);
+        let pairs: Vec<_> = WordConstraint::pairs(&word, 1).collect();
+        assert_eq!(vec![WordConstraint::PairAt(['A', 'C'], 0, 2, 4),
+                        WordConstraint::PairAt(['B', 'D'], 1, 3, 4)],
+                   pairs);
+        assert_eq!(0, WordConstraint::pairs(&word, 0).count());
+        assert_eq!(0, WordConstraint::pairs(&word[..2], 2).count());
+        assert_eq!(4 + 3 + 2 + 1, WordConstraint::all(&word, 2, 1).count());
+        assert_eq!(4 + 3 + 1, WordConstraint::all(&word, 2, 0).count());
+    }
+}
@@ -0,0 +1,170 @@
+use dict::Tag;
+use regex::{self, Regex};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+/// The reason why a `WordFilter` rejected an entry of a dictionary.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Rejection {
+    /// The word has fewer letters than the minimum word length.
+    TooShort,
+    /// The word has more letters than the maximum word length.
+    TooLong,
+    /// The word's score is below the minimum score.
+    LowScore,
+    /// The word is contained in one of the exclusion lists.
+    Excluded,
+    /// The entry is tagged with an excluded category.
+    Tagged(Tag),
+    /// The word matches one of the excluded patterns.
+    Pattern,
+    /// The word doesn't match one of the required patterns.
+    NoMatch,
+    /// The word is already contained in the earlier dictionary with the given index.
+    Duplicate(usize),
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Rejection::TooShort => write!(f, "too short"),
+            Rejection::TooLong => write!(f, "too long"),
+            Rejection::LowScore => write!(f, "score too low"),
+            Rejection::Excluded => write!(f, "in an exclusion list"),
+            Rejection::Tagged(tag) => write!(f, "tagged {}", tag),
+            Rejection::Pattern => write!(f, "matching an excluded pattern"),
+            Rejection::NoMatch => write!(f, "not matching a required pattern"),
+            Rejection::Duplicate(i) => write!(f, "already in dictionary {}", i + 1),
+        }
+    }
+}
+
+/// The number of entries each filter removed from a dictionary.
+pub type RejectionCounts = BTreeMap<Rejection, usize>;
+
+/// Decides which entries of the word lists are used in the puzzles.
+pub struct WordFilter {
+    min_word_len: usize,
+    max_word_len: usize,
+    min_score: i32,
+    excluded_words: HashSet<Vec<char>>,
+    excluded_tags: Vec<Tag>,
+    excluded_patterns: Vec<Regex>,
+    required_patterns: Vec<Regex>,
+}
+
+/// Compiles the regular expression so that it has to match whole words.
+fn whole_word_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{})$", pattern))
+}
+
+impl WordFilter {
+    /// Create a filter that only rejects words that are shorter than `min_word_len` or have a
+    /// score below `min_score`.
+    pub fn new(min_word_len: usize, min_score: i32) -> WordFilter {
+        WordFilter {
+            min_word_len,
+            max_word_len: usize::MAX,
+            min_score,
+            excluded_words: HashSet::new(),
+            excluded_tags: Vec::new(),
+            excluded_patterns: Vec::new(),
+            required_patterns: Vec::new(),
+        }
+    }
+
+    /// Reject words that are longer than `max_word_len`.
+    pub fn set_max_word_len(&mut self, max_word_len: usize) {
+        self.max_word_len = max_word_len;
+    }
+
+    /// Reject the given words.
+    pub fn exclude_words<T: IntoIterator<Item = Vec<char>>>(&mut self, words: T) {
+        self.excluded_words.extend(words);
+    }
+
+    /// Reject entries that are tagged with the given category.
+    pub fn exclude_tag(&mut self, tag: Tag) {
+        if !self.excluded_tags.contains(&tag) {
+            self.excluded_tags.push(tag);
+        }
+    }
+
+    /// Returns whether entries with any tags are rejected.
+    pub fn excludes_tags(&self) -> bool {
+        !self.excluded_tags.is_empty()
+    }
+
+    /// Reject words that match the regular expression as a whole, e. g. `.*ASS.*`. The words are
+    /// matched in their normalized form, in capital letters.
+    pub fn exclude_pattern(&mut self, pattern: &str) -> Result<(), regex::Error> {
+        self.excluded_patterns.push(whole_word_regex(pattern)?);
+        Ok(())
+    }
+
+    /// Reject words that don't match the regular expression as a whole.
+    pub fn require_pattern(&mut self, pattern: &str) -> Result<(), regex::Error> {
+        self.required_patterns.push(whole_word_regex(pattern)?);
+        Ok(())
+    }
+
+    /// Return the reason to reject the entry, or `None` if it can be used.
+    pub fn check(&self, word: &[char], score: i32, tags: &[Tag]) -> Option<Rejection> {
+        if word.len() < self.min_word_len {
+            return Some(Rejection::TooShort);
+        }
+        if word.len() > self.max_word_len {
+            return Some(Rejection::TooLong);
+        }
+        if score < self.min_score {
+            return Some(Rejection::LowScore);
+        }
+        if self.excluded_words.contains(word) {
+            return Some(Rejection::Excluded);
+        }
+        if let Some(&tag) = tags.iter().find(|tag| self.excluded_tags.contains(tag)) {
+            return Some(Rejection::Tagged(tag));
+        }
+        if !self.excluded_patterns.is_empty() || !self.required_patterns.is_empty() {
+            let string: String = word.iter().collect();
+            if self.excluded_patterns.iter().any(|regex| regex.is_match(&string)) {
+                return Some(Rejection::Pattern);
+            }
+            if !self.required_patterns.iter().all(|regex| regex.is_match(&string)) {
+                return Some(Rejection::NoMatch);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::*;
+
+    #[test]
+    fn test_word_filter() {
+        let mut filter = WordFilter::new(3, 10);
+        filter.exclude_words(strs_to_cvecs(&["FOO"]));
+        filter.exclude_tag(Tag::Obscure);
+        filter.exclude_pattern("B.R|.*X.*").unwrap();
+        let check = |word, score, tags: &[Tag]| filter.check(&str_to_cvec(word), score, tags);
+        assert_eq!(None, check("BAZ", 50, &[Tag::Trademark]));
+        assert_eq!(Some(Rejection::TooShort), check("BA", 50, &[]));
+        assert_eq!(Some(Rejection::LowScore), check("BAZ", 5, &[]));
+        assert_eq!(Some(Rejection::Excluded), check("FOO", 50, &[]));
+        assert_eq!(Some(Rejection::Tagged(Tag::Obscure)),
+                   check("QUUX", 50, &[Tag::Trademark, Tag::Obscure]));
+        assert_eq!(Some(Rejection::Pattern), check("BAR", 50, &[]));
+        assert_eq!(None, check("BARN", 50, &[]));
+        assert_eq!(Some(Rejection::Pattern), check("BOXES", 50, &[]));
+        assert!(filter.exclude_pattern("(").is_err());
+        filter.set_max_word_len(4);
+        filter.require_pattern("B.*").unwrap();
+        let check = |word, score, tags: &[Tag]| filter.check(&str_to_cvec(word), score, tags);
+        assert_eq!(Some(Rejection::TooLong), check("BAZZZ", 50, &[]));
+        assert_eq!(Some(Rejection::NoMatch), check("ZAB", 50, &[]));
+        assert_eq!(None, check("BAZ", 50, &[]));
+    }
+}
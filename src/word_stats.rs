@@ -1,6 +1,7 @@
 use cw::BLOCK;
+use dict::Dict;
 use std::cmp;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::iter;
 use std::usize;
 use word_constraint::WordConstraint;
@@ -8,32 +9,75 @@ use word_constraint::WordConstraint;
 /// A `WordStats` represents word frequency statistics for one or more dictionaries. It contains
 /// numbers of words satisfying each `WordConstraint` and using these can estimate numbers of words
 /// matching a given pattern.
-pub struct WordStats {
+pub struct WordStats<'a> {
+    /// The counts for words that were added individually.
     freq: HashMap<WordConstraint, usize>,
+    /// The dictionaries whose index is used for the counts, instead of copying them.
+    dicts: Vec<&'a Dict>,
     max_n: usize,
+    max_gap: usize,
     min_len: usize,
 }
 
-impl WordStats {
+impl<'a> WordStats<'a> {
     /// Create a new `WordStats` that will count word frequencies for n-grams of up to `max_n`
-    /// letters.
-    pub fn new(max_n: usize) -> WordStats {
+    /// letters, and for pairs of letters with up to `max_gap` letters between them.
+    pub fn new(max_n: usize, max_gap: usize) -> WordStats<'a> {
         WordStats {
             freq: HashMap::new(),
+            dicts: Vec::new(),
             max_n: max_n,
+            max_gap: max_gap,
             min_len: usize::MAX,
         }
     }
 
-    /// Add all words in the iterator.
-    pub fn add_words<'a, T: Iterator<Item = &'a Vec<char>>>(&mut self, words: T) {
+    /// Add all words in the iterator. Each word can be any sequence of characters, e.g. a `Word`.
+    pub fn add_words<T, U>(&mut self, words: T)
+        where T: IntoIterator<Item = U>,
+              U: IntoIterator<Item = char>
+    {
+        let mut buffer = Vec::new();
         for word in words {
-            self.add_word(word);
+            buffer.clear();
+            buffer.extend(word);
+            self.add_word(&buffer);
         }
     }
 
+    /// Add all words in the dictionary. If it contains all the necessary n-grams and pairs, this
+    /// borrows the dictionary and uses the sizes of its lists instead of counting the words. The
+    /// dictionary must not contain any words that have already been added.
+    pub fn add_dict(&mut self, dict: &'a Dict) {
+        if dict.get_max_n() < self.max_n || dict.get_max_gap() < self.max_gap {
+            return self.add_words(dict.all_words());
+        }
+        self.min_len = cmp::min(self.min_len, dict.get_min_len());
+        self.dicts.push(dict);
+    }
+
     fn get(&self, wc: &WordConstraint) -> usize {
-        *self.freq.get(wc).unwrap_or(&0)
+        let freq = if self.freq.is_empty() {
+            0
+        } else {
+            *self.freq.get(wc).unwrap_or(&0)
+        };
+        freq + self.dicts.iter().map(|dict| dict.get_count(wc)).sum::<usize>()
+    }
+
+    /// Call `f` with each constraint of up to `max_n` letters and a number of words satisfying it.
+    /// The same constraint can occur several times, and its counts need to be added up.
+    fn for_each_count<F: FnMut(&WordConstraint, usize)>(&self, mut f: F) {
+        for (wc, &count) in &self.freq {
+            f(wc, count);
+        }
+        for dict in &self.dicts {
+            for (wc, count) in dict.constraint_counts() {
+                if wc.ngram_len() <= self.max_n {
+                    f(&wc, count);
+                }
+            }
+        }
     }
 
     fn get_total(&self, len: usize) -> usize {
@@ -45,6 +89,39 @@ impl WordStats {
         self.min_len
     }
 
+    /// Return the number of words of each length.
+    pub fn length_counts(&self) -> BTreeMap<usize, usize> {
+        let mut counts = BTreeMap::new();
+        self.for_each_count(|wc, count| if let WordConstraint::Length(len) = *wc {
+                                *counts.entry(len).or_insert(0) += count;
+                            });
+        counts
+    }
+
+    /// Return the number of words with at most `max_len` letters.
+    pub fn count_up_to(&self, max_len: usize) -> usize {
+        self.length_counts().range(..=max_len).map(|(_, &count)| count).sum()
+    }
+
+    /// Return the number of occurrences of each letter in all words.
+    pub fn letter_counts(&self) -> BTreeMap<char, usize> {
+        let mut counts = BTreeMap::new();
+        self.for_each_count(|wc, count| if let WordConstraint::CharAt(c, _, _) = *wc {
+                                *counts.entry(c).or_insert(0) += count;
+                            });
+        counts
+    }
+
+    /// Return the number of distinct n-grams that occur anywhere in the words, for each n from 1
+    /// to `max_n`.
+    pub fn distinct_ngram_counts(&self) -> Vec<usize> {
+        let mut ngrams = vec![HashSet::new(); self.max_n];
+        self.for_each_count(|wc, _| if let Some(ngram) = wc.ngram() {
+                                ngrams[ngram.len() - 1].insert(ngram.to_vec());
+                            });
+        ngrams.iter().map(HashSet::len).collect()
+    }
+
     fn get_freq(&self, ngram: &[char], pos: usize, len: usize) -> usize {
         self.get(&WordConstraint::with_ngram(ngram, pos, len))
     }
@@ -57,7 +134,7 @@ impl WordStats {
     /// Increase the word count for each `WordConstraint` matching the given word.
     pub fn add_word(&mut self, word: &[char]) {
         self.min_len = cmp::min(self.min_len, word.len());
-        for wc in WordConstraint::all(word, self.max_n) {
+        for wc in WordConstraint::all(word, self.max_n, self.max_gap) {
             self.increase(wc);
         }
     }
@@ -90,6 +167,7 @@ impl WordStats {
         }
         let mut probability = 1.;
         let mut pos = 0;
+        let mut prev_last = None;
         for i in pattern
                 .iter()
                 .enumerate()
@@ -98,21 +176,61 @@ impl WordStats {
                 .chain(iter::once(len)) {
             if i > pos {
                 probability *= self.get_estimate(&pattern[pos..i], pos, len) / total;
+                if let Some(j) = prev_last {
+                    probability *= self.get_pair_correlation(pattern, j, pos, total);
+                }
                 if probability == 0. {
                     return 0.;
                 }
+                prev_last = Some(i - 1);
             }
             pos = i + 1;
         }
         probability * total
     }
+
+    /// Compute an estimate of the number of words that will match the given pattern, if the
+    /// letter at each of the given positions additionally has to be one of the given letters.
+    pub fn estimate_class_matches(&self, pattern: &[char], classes: &[(usize, Vec<char>)]) -> f32 {
+        let len = pattern.len();
+        let total = self.get_total(len) as f32;
+        if total == 0. {
+            return 0.;
+        }
+        classes.iter().fold(self.estimate_matches(pattern), |estimate, &(pos, ref letters)| {
+            let freq: usize = letters
+                .iter()
+                .map(|&c| self.get(&WordConstraint::CharAt(c, pos, len)))
+                .sum();
+            estimate * freq as f32 / total
+        })
+    }
+
+    /// Return the factor by which the probability of the letters at `pos0` and `pos1` appearing
+    /// together differs from the product of their individual probabilities. This corrects the
+    /// independence assumption between subwords separated by a gap. If the pair is not counted
+    /// because the gap is too wide, this is 1.
+    fn get_pair_correlation(&self, pattern: &[char], pos0: usize, pos1: usize, total: f32) -> f32 {
+        if pos1 - pos0 - 1 > self.max_gap {
+            return 1.;
+        }
+        let len = pattern.len();
+        let (c0, c1) = (pattern[pos0], pattern[pos1]);
+        let pair_freq = self.get(&WordConstraint::PairAt([c0, c1], pos0, pos1, len)) as f32;
+        if pair_freq == 0. {
+            return 0.;
+        }
+        let freq0 = self.get(&WordConstraint::CharAt(c0, pos0, len)) as f32;
+        let freq1 = self.get(&WordConstraint::CharAt(c1, pos1, len)) as f32;
+        pair_freq * total / (freq0 * freq1)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::collections::HashSet;
-    use test_util::str_to_cvec;
+    use test_util::*;
 
     // Results in this test are exact.
     #[cfg_attr(feature="cargo-clippy", allow(float_cmp))]
@@ -121,8 +239,8 @@ mod tests {
         let mut words: HashSet<Vec<char>> = HashSet::new();
         words.insert(str_to_cvec("ABCD"));
         words.insert(str_to_cvec("AXYZ"));
-        let mut ws = WordStats::new(2);
-        ws.add_words(words.iter());
+        let mut ws = WordStats::new(2, 1);
+        ws.add_words(words.iter().cloned());
         assert_eq!(1., ws.estimate_matches(&str_to_cvec("AB##")));
         assert_eq!(1., ws.estimate_matches(&str_to_cvec("#B##")));
         assert_eq!(0., ws.estimate_matches(&str_to_cvec("#AB#")));
@@ -134,4 +252,47 @@ mod tests {
         assert_eq!(1., ws.estimate_matches(&str_to_cvec("ABC#")));
         assert_eq!(0., ws.estimate_matches(&str_to_cvec("#C##")));
     }
+
+    #[test]
+    fn test_pairs() {
+        let words = strs_to_cvecs(&["ABCDE", "AXEYS", "XBEYS", "AYCZS"]);
+        let mut ws = WordStats::new(2, 1);
+        ws.add_words(words.iter().cloned());
+        assert_eq!(1., ws.estimate_matches(&str_to_cvec("A#E##")));
+        assert_eq!(0., ws.estimate_matches(&str_to_cvec("X#C##")));
+        assert_eq!(1., ws.estimate_matches(&str_to_cvec("#B#D#")));
+        let classes = [(2, str_to_cvec("C"))];
+        assert_eq!(1.5, ws.estimate_class_matches(&str_to_cvec("A####"), &classes));
+        let mut ws = WordStats::new(1, 0);
+        ws.add_words(words.iter().cloned());
+        assert_eq!(3. * 2. / 4., ws.estimate_matches(&str_to_cvec("A#E##")));
+        // The letters around a gap of two are only linked if such pairs are counted.
+        let mut ws = WordStats::new(2, 1);
+        ws.add_words(words.iter().cloned());
+        assert_eq!(3. * 2. / 4., ws.estimate_matches(&str_to_cvec("A##Y#")));
+        let mut ws = WordStats::new(2, 2);
+        ws.add_words(words.iter().cloned());
+        assert_eq!(1., ws.estimate_matches(&str_to_cvec("A##Y#")));
+    }
+
+    #[test]
+    fn test_add_dict() {
+        let words = strs_to_cvecs(&["ABCD", "AXYZ", "FOO", "BAR"]);
+        let mut ws = WordStats::new(2, 1);
+        ws.add_words(words.iter().cloned());
+        let dict = words_to_dict(words);
+        let mut dict_ws = WordStats::new(2, 1);
+        dict_ws.add_dict(&dict);
+        assert_eq!(ws.length_counts(), dict_ws.length_counts());
+        assert_eq!(ws.letter_counts(), dict_ws.letter_counts());
+        for pattern in &["A###", "#B##", "A#C#", "##O", "#X#Z"] {
+            let pattern = str_to_cvec(pattern);
+            assert_eq!(ws.estimate_matches(&pattern), dict_ws.estimate_matches(&pattern));
+        }
+        assert_eq!(3, dict_ws.get_min_len());
+        assert_eq!(vec![(3, 2), (4, 2)], dict_ws.length_counts().into_iter().collect::<Vec<_>>());
+        assert_eq!(Some(&3), dict_ws.letter_counts().get(&'A'));
+        assert_eq!(vec![10, 10], dict_ws.distinct_ngram_counts());
+        assert_eq!(2, dict_ws.count_up_to(3));
+    }
 }